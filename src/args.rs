@@ -0,0 +1,168 @@
+use std::env;
+use std::io::{stdin, Read};
+use std::path::Path;
+
+// crate version
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// usage for help
+const USAGE: &str = "\
+usage: fe [options] [+line[:col]] [file[:line[:col]]]...
+
+options:
+  -R, --readonly    open buffers read-only
+  -c <command>      run editor command on startup
+  --session <name>  restore saved session
+  -h, --help        show this help
+  -V, --version     show version
+
+use `-` as a file to read from stdin.";
+
+// file to open
+pub enum FileArg {
+    // file from path
+    Path(String),
+
+    // contents read from stdin
+    Stdin(String),
+}
+
+// file with position to jump to
+pub struct OpenArg {
+    // file to open
+    pub file: FileArg,
+
+    // position for goto
+    pub position: Option<String>,
+}
+
+// command-line arguments
+pub struct Args {
+    // files to open as buffers
+    pub files: Vec<OpenArg>,
+
+    // open buffers read-only
+    pub readonly: bool,
+
+    // commands to run on startup
+    pub commands: Vec<String>,
+
+    // session to restore
+    pub session: Option<String>,
+}
+
+impl Args {
+    // parse arguments, returning none if editor should not run
+    pub fn parse() -> Result<Option<Self>, String> {
+        let mut args = Self {
+            files:    Vec::new(),
+            readonly: false,
+            commands: Vec::new(),
+            session:  None,
+        };
+
+        // position for next file
+        let mut position = None;
+
+        // only files after `--`
+        let mut files_only = false;
+
+        let mut iter = env::args().skip(1);
+
+        while let Some(arg) = iter.next() {
+            if files_only {
+                args.push_file(FileArg::Path(arg), position.take());
+                continue;
+            }
+
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    return Ok(None);
+                }
+
+                "-V" | "--version" => {
+                    println!("ferrite {}", VERSION);
+                    return Ok(None);
+                }
+
+                "-R" | "--readonly" => args.readonly = true,
+
+                "-c" => {
+                    let command = iter
+                        .next()
+                        .ok_or("option `-c` requires a command")?;
+
+                    args.commands.push(command);
+                }
+
+                "--session" => {
+                    let name = iter
+                        .next()
+                        .ok_or("option `--session` requires a name")?;
+
+                    args.session = Some(name);
+                }
+
+                "--" => files_only = true,
+
+                "-" => {
+                    let mut contents = String::new();
+
+                    stdin()
+                        .read_to_string(&mut contents)
+                        .map_err(|err| format!("unable to read stdin: {}", err))?;
+
+                    args.push_file(FileArg::Stdin(contents), position.take());
+                }
+
+                _ => {
+                    if let Some(pos) = arg.strip_prefix('+') {
+                        position = Some(pos.to_string());
+                    } else if arg.starts_with('-') {
+                        return Err(format!("unknown option `{}`", arg));
+                    } else {
+                        let (path, pos) = Self::split_position(&arg);
+                        args.push_file(FileArg::Path(path), pos.or(position.take()));
+                    }
+                }
+            }
+        }
+
+        Ok(Some(args))
+    }
+
+    // add file to open
+    fn push_file(&mut self, file: FileArg, position: Option<String>) {
+        self.files.push(OpenArg { file, position });
+    }
+
+    // split `file:line:col` into path and position
+    fn split_position(arg: &str) -> (String, Option<String>) {
+        // prefer existing paths containing colons
+        if Path::new(arg).exists() {
+            return (arg.to_string(), None);
+        }
+
+        let mut parts = arg.rsplitn(3, ':').collect::<Vec<&str>>();
+        parts.reverse();
+
+        let is_num = |part: &&str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+
+        match parts.as_slice() {
+            [path, line, col] if is_num(line) && is_num(col) => {
+                (path.to_string(), Some(format!("{}:{}", line, col)))
+            }
+
+            [path, line, col] if is_num(col) => {
+                (format!("{}:{}", path, line), Some(col.to_string()))
+            }
+
+            [path, line] if is_num(line) => {
+                (path.to_string(), Some(line.to_string()))
+            }
+
+            _ => (arg.to_string(), None),
+        }
+    }
+}
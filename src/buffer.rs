@@ -1,24 +1,40 @@
 use crate::syntax::*;
 
 use crate::utils::prompt;
+use crate::config::Config;
 use crate::contents::Contents;
 use crate::cursor::Cursor;
+use crate::encoding::Encoding;
+use crate::history::{EditKind, History};
 use crate::message::Message;
 use crate::rows::Rows;
+use crate::picker::Overlay;
+use crate::recent::Recent;
 use crate::search::SearchIndex;
+use crate::swap::Swap;
 
-use crossterm::{cursor, queue, terminal, Result};
+#[cfg(feature = "tree-sitter")]
+use crate::treesitter::TreeSitterHighlight;
+
+use crossterm::{cursor, queue, terminal};
 use crossterm::event::KeyCode;
 use crossterm::terminal::ClearType;
-use crossterm::style::Attribute;
+use crossterm::style::{Attribute, SetAttribute};
 
-use std::cmp::min;
-use std::io::Write;
-use std::path::PathBuf;
+use std::cmp::{min, Ordering};
+use std::ops::Range;
+use std::io::{Write, Result};
+use std::path::{Path, PathBuf};
 
 // crate version
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// recent files shown on welcome screen
+const RECENT_SHOWN: usize = 5;
+
+// rows past screen searched for highlighted bracket match
+const MATCH_MARGIN: usize = 100;
+
 // buffer for file
 pub struct Buffer {
     // writable contents
@@ -36,6 +52,12 @@ pub struct Buffer {
     // search index
     search_idx: SearchIndex,
 
+    // selection anchor
+    anchor: Option<(usize, usize)>,
+
+    // undo history
+    history: History,
+
     // syntax highlighting
     pub syntax: Option<Box<dyn SyntaxHighlight>>,
 
@@ -50,11 +72,23 @@ pub struct Buffer {
 
     // dirty status
     pub dirty: u64,
+
+    // prevent edits and saves
+    pub readonly: bool,
+
+    // recovery file for unsaved changes
+    pub swap: Swap,
+
+    // list drawn over rows
+    pub overlay: Option<Overlay>,
+
+    // recent files shown on welcome screen
+    recent: Vec<String>,
 }
 
 impl Buffer {
     // create new output
-    pub fn new(file: Option<String>) -> Self {
+    pub fn new(file: Option<String>) -> Result<Self> {
         // get term size
         let term_size = terminal::size()
             .map(|(x, y)| (
@@ -64,30 +98,112 @@ impl Buffer {
             .unwrap();
 
         let mut syntax = None;
+        let mut message = Message::new(String::new());
+
+        // show message for new files
+        if file.as_ref().is_some_and(|path| !Path::new(path).exists()) {
+            message.set_message(String::from("[new file]"));
+        }
+
+        let rows = Rows::new(file, &mut syntax)?;
+
+        // open binary files read-only
+        let readonly = rows.binary;
 
-        Self {
+        if readonly {
+            message.set_message(String::from("[warning] binary file opened read-only"));
+        }
+
+        // unnamed buffers show recent files on welcome screen
+        let recent = match &rows.filepath {
+            Some(path) => {
+                Recent::add(path);
+                Vec::new()
+            }
+
+            None => Recent::load()
+                .files()
+                .iter()
+                .take(RECENT_SHOWN)
+                .map(|path| Recent::display(path))
+                .collect(),
+        };
+
+        // restore undo history saved for file
+        let history = match (&rows.filepath, &rows.stamp) {
+            (Some(path), Some(stamp)) if Config::get_config().files.persistent_undo => {
+                History::load(path, &stamp.contents_key())
+            }
+
+            _ => None,
+        };
+
+        Ok(Self {
             contents:   Contents::new(),
             cursor:     Cursor::new(term_size),
-            rows:       Rows::new(file, &mut syntax),
-            message:    Message::new(String::new()),
+            rows,
+            message,
             search_idx: SearchIndex::new(),
+            anchor:     None,
+            history:    history.unwrap_or_else(History::new),
             buffers:    Vec::new(),
 
             current_buf: 0,
             dirty: 0,
+            readonly,
+            swap: Swap::new(),
+            overlay: None,
+            recent,
 
             syntax,
             term_size,
+        })
+    }
+
+    // create unnamed buffer from contents
+    pub fn from_contents(contents: &str) -> Result<Self> {
+        let mut buffer = Self::new(None)?;
+
+        buffer.rows.set_contents(
+            contents
+                .lines()
+                .map(String::from)
+                .collect(),
+        );
+
+        // contents are not saved anywhere
+        buffer.dirty = 1;
+        Ok(buffer)
+    }
+
+    // check if buffer is read-only, showing warning
+    fn check_readonly(&mut self) -> bool {
+        if self.readonly {
+            self.message.set_message(String::from("[warning] buffer is read-only"));
         }
+
+        self.readonly
+    }
+
+    // get syntax for file path
+    pub fn syntax_for_path(path: &Path) -> Option<Box<dyn SyntaxHighlight>> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::get_syntax)
     }
 
     // get syntax for file type
     pub fn get_syntax(extension: &str) -> Option<Box<dyn SyntaxHighlight>> {
         // available syntaxes
-        let syntaxes: Vec<Box<dyn SyntaxHighlight>> = vec![
-            Box::new(RustHighlight::new()),
-            Box::new(JavascriptHighlight::new()),
-        ];
+        let mut syntaxes: Vec<Box<dyn SyntaxHighlight>> = Vec::new();
+
+        // prefer tree-sitter grammars when enabled
+        #[cfg(feature = "tree-sitter")]
+        syntaxes.extend(TreeSitterHighlight::grammars());
+
+        syntaxes.push(Box::new(RustHighlight::new()));
+        syntaxes.push(Box::new(JavascriptHighlight::new()));
+        syntaxes.push(Box::new(PythonHighlight::new()));
 
         syntaxes.into_iter()
             .find(|syntax| {
@@ -99,9 +215,692 @@ impl Buffer {
 
     // move cursor
     pub fn move_cursor(&mut self, dir: KeyCode) {
+        self.anchor = None;
+        self.history.break_group();
         self.cursor.move_cursor(dir, &self.rows);
     }
 
+    // move cursor by word
+    pub fn move_word(&mut self, forward: bool) {
+        self.anchor = None;
+        self.history.break_group();
+
+        let syntax = self.syntax.as_deref();
+
+        self.cursor.move_word(forward, &self.rows, |c| {
+            syntax.map_or_else(|| is_separator(c), |it| it.is_separator(c))
+        });
+    }
+
+    // move cursor to start or end of document
+    pub fn move_document(&mut self, end: bool) {
+        self.anchor = None;
+        self.history.break_group();
+        self.cursor.move_document(end, &self.rows);
+    }
+
+    // save undo history for file on disk
+    pub fn save_history(&mut self) {
+        // history only matches saved contents
+        if !Config::get_config().files.persistent_undo || self.dirty > 0 || self.history.is_empty() {
+            return;
+        }
+
+        if let (Some(path), Some(stamp)) = (&self.rows.filepath, &self.rows.stamp) {
+            if let Err(err) = self.history.save(path, &stamp.contents_key()) {
+                self.message.set_message(format!("[error] unable to save undo history: {}", err));
+            }
+        }
+    }
+
+    // get cursor position and scroll offsets
+    pub fn position(&self) -> ((usize, usize), (usize, usize)) {
+        (
+            (self.cursor.x, self.cursor.y),
+            (self.cursor.row_offset, self.cursor.col_offset),
+        )
+    }
+
+    // restore cursor position and scroll offsets
+    pub fn set_position(&mut self, (x, y): (usize, usize), (row, col): (usize, usize)) {
+        (self.cursor.x, self.cursor.y) = (x, y);
+        self.clamp_cursor();
+
+        self.cursor.row_offset = row;
+        self.cursor.col_offset = col;
+    }
+
+    // keep cursor inside contents after replacing them
+    fn clamp_cursor(&mut self) {
+        self.cursor.y = min(self.cursor.y, self.rows.num_rows());
+
+        if self.cursor.y < self.rows.num_rows() {
+            let content = &self.rows.get_row(self.cursor.y).content;
+            self.cursor.x = min(self.cursor.x, content.len());
+
+            while !content.is_char_boundary(self.cursor.x) {
+                self.cursor.x -= 1;
+            }
+        } else {
+            self.cursor.x = 0;
+        }
+
+        self.anchor = None;
+    }
+
+    // replace contents with recovered swap
+    pub fn recover(&mut self, contents: &str) {
+        self.record(EditKind::Other, 0..self.rows.num_rows());
+        self.history.break_group();
+
+        self.rows.set_contents(
+            contents
+                .split('\n')
+                .map(String::from)
+                .collect(),
+        );
+
+        if let Some(it) = &self.syntax {
+            it.update_all(&mut self.rows.rows);
+        }
+
+        self.clamp_cursor();
+        self.dirty += 1;
+
+        self.message.set_message(String::from("recovered from swap"));
+    }
+
+    // reload contents from disk
+    pub fn reload(&mut self) {
+        let path = match &self.rows.filepath {
+            Some(path) => path.to_string_lossy().into_owned(),
+            None => return,
+        };
+
+        let mut syntax = None;
+
+        match Rows::new(Some(path), &mut syntax) {
+            Ok(rows) => {
+                // allow undoing reload
+                self.record(EditKind::Other, 0..self.rows.num_rows());
+                self.history.break_group();
+
+                self.readonly |= rows.binary;
+                self.rows   = rows;
+                self.syntax = syntax;
+
+                self.clamp_cursor();
+                self.dirty = 0;
+
+                self.message.set_message(String::from("reloaded from disk"));
+            }
+
+            Err(err) => {
+                self.message.set_message(format!("[error] unable to reload file: {}", err));
+            }
+        }
+    }
+
+    // change encoding used on save
+    pub fn set_encoding(&mut self, name: &str) {
+        if self.check_readonly() {
+            return;
+        }
+
+        match Encoding::from_name(name) {
+            Some(encoding) => {
+                if encoding != self.rows.encoding {
+                    self.rows.encoding = encoding;
+                    self.dirty += 1;
+                }
+
+                self.message.set_message(format!("encoding set to {}", encoding.name()));
+            }
+
+            None => {
+                self.message.set_message(format!("[error] unknown encoding `{}`", name));
+            }
+        }
+    }
+
+    // go to line and column from input
+    pub fn goto(&mut self, input: &str) {
+        let num_rows = self.rows.num_rows();
+
+        let (line, col) = match input.trim().split_once(':') {
+            Some((line, col)) => (line, Some(col)),
+            None => (input.trim(), None),
+        };
+
+        let y =
+            if let Some(percent) = line.strip_suffix('%') {
+                // percentage of document
+                percent
+                    .parse::<usize>()
+                    .ok()
+                    .map(|p| num_rows.saturating_sub(1) * p.min(100) / 100)
+            } else if let Some(n) = line.strip_prefix('+') {
+                n.parse::<usize>().ok().map(|n| self.cursor.y.saturating_add(n))
+            } else if let Some(n) = line.strip_prefix('-') {
+                n.parse::<usize>().ok().map(|n| self.cursor.y.saturating_sub(n))
+            } else {
+                line.parse::<usize>().ok().map(|n| n.saturating_sub(1))
+            };
+
+        let x = match col {
+            Some(col) => col.parse::<usize>().ok().map(|n| n.saturating_sub(1)),
+            None => Some(0),
+        };
+
+        let (x, y) = match (x, y) {
+            (Some(x), Some(y)) => (x, y),
+            _ => {
+                self.message.set_message(format!("invalid position `{}`", input));
+                return;
+            }
+        };
+
+        // clamp position to rows
+        let y = min(y, num_rows.saturating_sub(1));
+        let x = min(x, self.rows.rows.get(y).map_or(0, |row| row.content.len()));
+
+        self.anchor = None;
+        self.history.break_group();
+        self.cursor.goto(x, y);
+    }
+
+    // move cursor while selecting
+    pub fn select_cursor(&mut self, dir: KeyCode) {
+        self.anchor.get_or_insert((self.cursor.x, self.cursor.y));
+        self.history.break_group();
+        self.cursor.move_cursor(dir, &self.rows);
+    }
+
+    // record undo step before edit changes rows
+    fn record(&mut self, kind: EditKind, rows: Range<usize>) {
+        let contents = &self.rows;
+        let cursor   = (self.cursor.x, self.cursor.y);
+
+        // only copy rows being changed
+        self.history.record(kind, rows, contents.num_rows(), cursor, |range| {
+            contents.contents_of(range)
+        });
+    }
+
+    // restore cursor after undo or redo
+    fn restore(&mut self, cursor: (usize, usize)) {
+        if let Some(it) = &self.syntax {
+            it.update_all(&mut self.rows.rows);
+        }
+
+        (self.cursor.x, self.cursor.y) = cursor;
+
+        self.anchor = None;
+        self.dirty += 1;
+    }
+
+    // undo last edit
+    pub fn undo(&mut self) {
+        if self.check_readonly() {
+            return;
+        }
+
+        let len    = self.rows.num_rows();
+        let cursor = (self.cursor.x, self.cursor.y);
+        let rows   = &mut self.rows;
+
+        match self.history.undo(len, cursor, |range, contents| rows.replace_rows(range, contents)) {
+            Some(cursor) => self.restore(cursor),
+            None => self.message.set_message(String::from("nothing to undo")),
+        }
+    }
+
+    // redo last undo
+    pub fn redo(&mut self) {
+        if self.check_readonly() {
+            return;
+        }
+
+        let len    = self.rows.num_rows();
+        let cursor = (self.cursor.x, self.cursor.y);
+        let rows   = &mut self.rows;
+
+        match self.history.redo(len, cursor, |range, contents| rows.replace_rows(range, contents)) {
+            Some(cursor) => self.restore(cursor),
+            None => self.message.set_message(String::from("nothing to redo")),
+        }
+    }
+
+    // get rows covered by selection or cursor
+    fn selected_rows(&self) -> Range<usize> {
+        let rows = match self.selection() {
+            // skip last row if selection ends at its start
+            Some(((_, sy), (ex, ey))) if sy != ey => {
+                sy..if ex == 0 { ey } else { ey + 1 }
+            }
+
+            _ => self.cursor.y..self.cursor.y + 1,
+        };
+
+        rows.start..min(rows.end, self.rows.num_rows())
+    }
+
+    // insert text into row, shifting cursor and anchor
+    fn insert_str(&mut self, (x, y): (usize, usize), text: &str) {
+        self.rows.get_mut_row(y).insert_str(x, text);
+
+        let len = text.len();
+        self.edit_syntax(RowsEdit::insert((x, y), (x + len, y), len));
+
+        let shift = |pos: &mut (usize, usize)| {
+            if pos.1 == y && pos.0 >= x {
+                pos.0 += len;
+            }
+        };
+
+        let mut cursor = (self.cursor.x, self.cursor.y);
+        shift(&mut cursor);
+        (self.cursor.x, self.cursor.y) = cursor;
+
+        if let Some(anchor) = self.anchor.as_mut() {
+            shift(anchor);
+        }
+    }
+
+    // remove text from row, shifting cursor and anchor
+    fn remove_str(&mut self, (x, y): (usize, usize), len: usize) {
+        self.rows.get_mut_row(y).delete_range(x..x + len);
+        self.edit_syntax(RowsEdit::delete((x, y), (x + len, y), len));
+
+        let shift = |pos: &mut (usize, usize)| {
+            if pos.1 == y && pos.0 > x {
+                pos.0 = pos.0.saturating_sub(len).max(x);
+            }
+        };
+
+        let mut cursor = (self.cursor.x, self.cursor.y);
+        shift(&mut cursor);
+        (self.cursor.x, self.cursor.y) = cursor;
+
+        if let Some(anchor) = self.anchor.as_mut() {
+            shift(anchor);
+        }
+    }
+
+    // indent selected rows or insert indentation
+    pub fn indent_rows(&mut self) {
+        if self.check_readonly() {
+            return;
+        }
+
+        let multiline = self
+            .selection()
+            .is_some_and(|((_, sy), (_, ey))| sy != ey);
+
+        self.record(EditKind::Other, self.selected_rows());
+
+        if !multiline {
+            self.anchor = None;
+
+            for chr in Rows::indent_unit().chars() {
+                self.insert_char(chr);
+            }
+
+            return;
+        }
+
+        let unit = Rows::indent_unit();
+
+        for y in self.selected_rows() {
+            // leave empty rows empty
+            if !self.rows.get_content(y).is_empty() {
+                self.insert_str((0, y), &unit);
+            }
+        }
+
+        self.update_rows(self.selected_rows());
+        self.dirty += 1;
+    }
+
+    // dedent selected rows
+    pub fn dedent_rows(&mut self) {
+        if self.check_readonly() {
+            return;
+        }
+
+        // skip undo step when nothing is indented
+        if self.selected_rows().all(|y| self.rows.indentation(y).is_empty()) {
+            return;
+        }
+
+        self.record(EditKind::Other, self.selected_rows());
+
+        for y in self.selected_rows() {
+            let indent = self.rows.indentation(y).len();
+            let len = self.rows.dedent_len(y);
+
+            self.remove_str((indent - len, y), len);
+        }
+
+        self.update_rows(self.selected_rows());
+        self.dirty += 1;
+    }
+
+    // toggle comments for selected rows
+    pub fn toggle_comment(&mut self) {
+        if self.check_readonly() {
+            return;
+        }
+
+        let (line, block) = match &self.syntax {
+            Some(syntax) => (
+                syntax.comment_start().to_string(),
+                syntax
+                    .multiline_comment()
+                    .map(|(start, end)| (start.to_string(), end.to_string())),
+            ),
+
+            None => (String::new(), None),
+        };
+
+        if line.is_empty() && block.is_none() {
+            self.message.set_message(String::from("no comment syntax for file type"));
+            return;
+        }
+
+        // rows with contents
+        let filled = self
+            .selected_rows()
+            .filter(|&y| !self.rows.get_content(y).trim().is_empty())
+            .collect::<Vec<usize>>();
+
+        let (first, last) = match (filled.first(), filled.last()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => return,
+        };
+
+        self.record(EditKind::Other, self.selected_rows());
+
+        // align comments at minimum indentation
+        let indent = filled
+            .iter()
+            .map(|&y| self.rows.indentation(y).len())
+            .min()
+            .unwrap_or(0);
+
+        // length of delimiter with trailing space at position
+        let delim_len = |rows: &Rows, (x, y): (usize, usize), delim: &str| {
+            delim.len() + rows.get_content(y)[x + delim.len()..].starts_with(' ') as usize
+        };
+
+        if !line.is_empty() {
+            let commented = filled
+                .iter()
+                .all(|&y| self.rows.get_content(y).trim_start().starts_with(&line));
+
+            for &y in &filled {
+                if commented {
+                    let at = self.rows.indentation(y).len();
+                    let len = delim_len(&self.rows, (at, y), &line);
+
+                    self.remove_str((at, y), len);
+                } else {
+                    self.insert_str((indent, y), &format!("{} ", line));
+                }
+            }
+        } else if let Some((start, end)) = block {
+            let first_content = self.rows.get_content(first);
+            let last_content  = self.rows.get_content(last);
+
+            // delimiters must not overlap on single row
+            let open_end  = self.rows.indentation(first).len() + start.len();
+            let close_pos = last_content.trim_end().len().saturating_sub(end.len());
+
+            let commented =
+                first_content.trim_start().starts_with(&start) &&
+                last_content.trim_end().ends_with(&end) &&
+                (first != last || open_end <= close_pos);
+
+            // change end of block first to keep start positions
+            if commented {
+                let content = self.rows.get_content(last);
+                let mut at = content.trim_end().len() - end.len();
+
+                // remove space before end
+                if content[..at].ends_with(' ') {
+                    at -= 1;
+                }
+
+                let len = content.trim_end().len() - at;
+                self.remove_str((at, last), len);
+
+                let at = self.rows.indentation(first).len();
+                let len = delim_len(&self.rows, (at, first), &start);
+
+                self.remove_str((at, first), len);
+            } else {
+                let at = self.rows.get_content(last).len();
+
+                self.insert_str((at, last), &format!(" {}", end));
+                self.insert_str((indent, first), &format!("{} ", start));
+            }
+        }
+
+        self.update_rows(self.selected_rows());
+        self.dirty += 1;
+    }
+
+    // update syntax for rows
+    fn update_rows(&mut self, rows: Range<usize>) {
+        if let Some(it) = &self.syntax {
+            it.update_rows(rows, &mut self.rows.rows);
+        }
+    }
+
+    // get selection start and end
+    pub fn selection(&self) -> Option<((usize, usize), (usize, usize))> {
+        let anchor = self.anchor?;
+        let cursor = (self.cursor.x, self.cursor.y);
+
+        // order positions by row then column
+        let key = |(x, y): (usize, usize)| (y, x);
+
+        match key(anchor).cmp(&key(cursor)) {
+            Ordering::Less    => Some((anchor, cursor)),
+            Ordering::Greater => Some((cursor, anchor)),
+            Ordering::Equal   => None,
+        }
+    }
+
+    // get pairs for auto closing
+    fn pairs(&self) -> Vec<(char, char)> {
+        match &self.syntax {
+            Some(syntax) => syntax.pairs(),
+            None => {
+                BRACKETS
+                    .iter()
+                    .copied()
+                    .chain([('"', '"'), ('\'', '\'')])
+                    .collect()
+            }
+        }
+    }
+
+    // get char at cursor offset
+    fn char_at(&self, offset: isize) -> Option<char> {
+        let x = self.cursor.x.checked_add_signed(offset)?;
+
+        self.rows.rows
+            .get(self.cursor.y)?
+            .content
+            .get(x..)?
+            .chars()
+            .next()
+    }
+
+    // check if cursor is inside empty pair
+    fn in_empty_pair(&self) -> bool {
+        let (prev, next) = (self.char_at(-1), self.char_at(0));
+
+        Config::get_config().pairs.auto && self
+            .pairs()
+            .into_iter()
+            .any(|(open, close)| prev == Some(open) && next == Some(close))
+    }
+
+    // type char with auto pairing and indentation
+    pub fn type_char(&mut self, chr: char) {
+        if self.check_readonly() {
+            return;
+        }
+
+        let selection = self.selection();
+
+        // selection may be wrapped in pair
+        let rows = match selection {
+            Some(((_, sy), (_, ey))) => sy..ey + 1,
+            None => self.cursor.y..self.cursor.y + 1,
+        };
+
+        self.record(EditKind::Insert, rows);
+
+        self.anchor = None;
+
+        let config = Config::get_config();
+
+        if config.pairs.auto && self.auto_pair(chr, selection) {
+            return;
+        }
+
+        self.insert_char(chr);
+
+        // align closer of block with its opener
+        if config.indent.auto && BRACKETS.iter().any(|(_, close)| *close == chr) {
+            self.align_closer();
+        }
+    }
+
+    // insert char as pair, returning if handled
+    fn auto_pair(&mut self, chr: char, selection: Option<((usize, usize), (usize, usize))>) -> bool {
+        let pairs = self.pairs();
+        let pair = pairs.iter().find(|(open, _)| *open == chr);
+
+        // wrap selection in pair
+        if let (Some(&(open, close)), Some((start, end))) = (pair, selection) {
+            self.cursor.x = end.0;
+            self.cursor.y = end.1;
+            self.insert_char(close);
+
+            self.cursor.x = start.0;
+            self.cursor.y = start.1;
+            self.insert_char(open);
+
+            // keep wrapped text selected
+            self.anchor = Some(
+                if start.1 == end.1 { (end.0 + 1, end.1) }
+                else { end }
+            );
+
+            return true;
+        }
+
+        // type over existing closer
+        if self.char_at(0) == Some(chr) && pairs.iter().any(|(_, close)| *close == chr) {
+            self.cursor.x += 1;
+            return true;
+        }
+
+        if let Some(&(open, close)) = pair {
+            let next = self.char_at(0);
+            let prev = self.char_at(-1);
+
+            // only pair before whitespace or closers
+            let before_space = next.is_none_or(|c| {
+                c.is_whitespace() || pairs.iter().any(|(_, close)| *close == c)
+            });
+
+            // don't pair quotes after words
+            let after_word = open == close && prev.is_some_and(char::is_alphanumeric);
+
+            if before_space && !after_word {
+                self.insert_char(open);
+                self.insert_char(close);
+                self.cursor.x -= 1;
+
+                return true;
+            }
+        }
+
+        false
+    }
+
+    // indent closer typed after indentation like row of its opener
+    fn align_closer(&mut self) {
+        let y = self.cursor.y;
+
+        // closer was typed before cursor
+        let x = match self.cursor.x.checked_sub(1) {
+            Some(x) if y < self.rows.num_rows() => x,
+            _ => return,
+        };
+
+        if !self.rows.get_content(y)[..x].chars().all(char::is_whitespace) {
+            return;
+        }
+
+        let open_y = match self.rows.find_match(x, y, usize::MAX) {
+            Some((_, open_y)) => open_y,
+            None => return,
+        };
+
+        let indent = self.rows.indentation(open_y).to_string();
+
+        if self.rows.get_content(y)[..x] == indent {
+            return;
+        }
+
+        self.remove_str((0, y), x);
+        self.insert_str((0, y), &indent);
+
+        self.update_rows(y..y + 1);
+    }
+
+    // get chars increasing indentation
+    fn indent_triggers(&self) -> Vec<char> {
+        match &self.syntax {
+            Some(syntax) => syntax.indent_triggers().to_vec(),
+            None => BRACKETS.iter().map(|(open, _)| *open).collect(),
+        }
+    }
+
+    // get bracket pair at or before cursor, searching up to max rows
+    fn bracket_pair(&self, max_rows: usize) -> Option<((usize, usize), (usize, usize))> {
+        let (x, y) = (self.cursor.x, self.cursor.y);
+
+        if y >= self.rows.num_rows() {
+            return None;
+        }
+
+        [Some(x), x.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .find_map(|x| {
+                self.rows
+                    .find_match(x, y, max_rows)
+                    .map(|pos| ((x, y), pos))
+            })
+    }
+
+    // jump to matching bracket
+    pub fn jump_to_match(&mut self) {
+        if let Some((_, (x, y))) = self.bracket_pair(usize::MAX) {
+            self.cursor.x = x;
+            self.cursor.y = y;
+        } else {
+            self.message.set_message(String::from("no matching bracket"));
+        }
+    }
+
     // draw tabs
     fn draw_tabline(&mut self) {
         // get length of tabline
@@ -146,7 +945,7 @@ impl Buffer {
                 if len > &self.term_size.0 {
                     filename = &filename[..min(
                         filename.len(),
-                        (self.term_size.0 / (self.buffers.len() * 3)) as usize,
+                        self.term_size.0 / (self.buffers.len() * 3),
                     )];
                 }
 
@@ -208,19 +1007,35 @@ impl Buffer {
             if self.dirty > 0 { " +" }
             else { "" };
 
+        let readonly =
+            if self.readonly { " [ro]" }
+            else { "" };
+
+        let noeol =
+            if self.rows.has_final_newline() { "" }
+            else { " [noeol]" };
+
+        let line_ending =
+            if self.rows.line_ending() == "\r\n" { "crlf" }
+            else { "lf" };
+
         let filetype = self.syntax
             .as_ref()
             .map(|highlight| highlight.filetype())
             .unwrap_or("no ft");
 
         let left_seg = format!(
-            " {}{} |",
+            " {}{}{}{} |",
             filename,
             dirty,
+            readonly,
+            noeol,
         );
         let right_seg = format!(
-            "| {} | {}, {} ",
+            "| {} | {} | {} | {}, {} ",
             filetype,
+            self.rows.encoding.name(),
+            line_ending,
             self.cursor.y + 1,
             self.cursor.x + 1,
         );
@@ -251,11 +1066,12 @@ impl Buffer {
         )?;
 
         if let Some(msg) = self.message.message() {
-            self.contents
-                .push_str(&msg[..min(
-                    self.term_size.0,
-                    msg.len(),
-                )]);
+            let msg = msg
+                .chars()
+                .take(self.term_size.0)
+                .collect::<String>();
+
+            self.contents.push_str(&msg);
         }
 
         Ok(())
@@ -293,27 +1109,45 @@ impl Buffer {
         self.contents.push_str("\r\n");
         self.draw_tabline();
 
+        if self.overlay.is_some() {
+            return self.draw_overlay();
+        }
+
+        // bracket pair at cursor, only searching near screen
+        let brackets = self.bracket_pair(self.term_size.1 + MATCH_MARGIN)
+            .map(|(open, close)| vec![open, close])
+            .unwrap_or_default();
+
+        let selection = self.selection();
+
+        let main_msg = format!("ferrite editor v{}", VERSION);
+
+        let mut messages = vec![
+            main_msg,
+            String::from("a rust-powered editor"),
+            String::new(),
+            String::from("-- keybindings --"),
+            String::from("ctrl-q | quit"),
+            String::from("ctrl-s | save"),
+        ];
+
+        // list recent files on welcome screen
+        if self.rows.num_rows() == 0 && !self.recent.is_empty() {
+            messages.push(String::new());
+            messages.push(String::from("-- recent files --"));
+            messages.extend(self.recent.iter().cloned());
+        }
+
         for i in 1..rows {
             // row with offset
             let row_num = i - 1 + self.cursor.row_offset;
 
             if row_num >= self.rows.num_rows() {
-                let main_msg = format!("ferrite editor v{}", VERSION);
-
-                let messages = vec![
-                    main_msg.as_str(),
-                    "a rust-powered editor",
-                    "",
-                    "-- keybindings --",
-                    "ctrl-q | quit",
-                    "ctrl-s | save",
-                ];
-
                 let mut drew_message = false;
 
                 for (m, msg) in messages.iter().enumerate() {
                     if self.rows.num_rows() == 0 && i == rows / 4 + m {
-                        self.draw_message(String::from(*msg));
+                        self.draw_message(msg.clone());
                         drew_message = true;
                         break;
                     }
@@ -346,6 +1180,37 @@ impl Buffer {
                     if len == 0 { 0 }
                     else { col_offset };
 
+                // highlighting for visible render
+                let mut highlight =
+                    if row.highlight.len() == render.len() {
+                        row.highlight[start..start+len].to_vec()
+                    } else {
+                        vec![HighlightType::Normal; len]
+                    };
+
+                // highlight selected text
+                if let Some(((sx, sy), (ex, ey))) = selection {
+                    if (sy..=ey).contains(&row_num) {
+                        let indices = row.render_indices();
+
+                        let from = if row_num == sy { indices[sx] } else { 0 };
+                        let to   = if row_num == ey { indices[ex] } else { render.len() };
+
+                        for idx in from.max(start)..to.min(start + len) {
+                            highlight[idx - start] = HighlightType::Selection;
+                        }
+                    }
+                }
+
+                // highlight matching brackets
+                for &(x, _) in brackets.iter().filter(|(_, y)| *y == row_num) {
+                    let idx = row.render_indices()[x];
+
+                    if (start..start+len).contains(&idx) {
+                        highlight[idx - start] = HighlightType::MatchBracket;
+                    }
+                }
+
                 self.syntax
                     .as_ref()
                     .map(|syntax| {
@@ -354,7 +1219,7 @@ impl Buffer {
                             row_num + 1,
                             self.rows.num_rows(),
                             &render[start..start+len],
-                            &row.highlight[start..start+len],
+                            &highlight,
                             &mut self.contents,
                         )
                     })
@@ -369,9 +1234,16 @@ impl Buffer {
                                 .len(),
                         ));
 
-                        self.contents.push_str(
-                            &render[start..start+len],
-                        );
+                        for (chr, highlight) in render[start..start+len].chars().zip(&highlight) {
+                            // reverse matching brackets and selection
+                            if let HighlightType::MatchBracket | HighlightType::Selection = highlight {
+                                queue!(self.contents, SetAttribute(Attribute::Reverse))?;
+                                self.contents.push(chr);
+                                queue!(self.contents, SetAttribute(Attribute::NoReverse))?;
+                            } else {
+                                self.contents.push(chr);
+                            }
+                        }
 
                         Ok(())
                     })?;
@@ -389,6 +1261,40 @@ impl Buffer {
         Ok(())
     }
 
+    // draw overlay list instead of rows
+    fn draw_overlay(&mut self) -> Result<()> {
+        let cols   = self.term_size.0;
+        let height = self.term_size.1 - 1;
+
+        let mut lines = self.overlay
+            .as_ref()
+            .map(|overlay| overlay.lines(height))
+            .unwrap_or_default();
+
+        lines.resize(height, (String::new(), false));
+
+        for (line, highlighted) in lines {
+            let line = line.chars().take(cols).collect::<String>();
+
+            if highlighted {
+                queue!(self.contents, SetAttribute(Attribute::Reverse))?;
+                self.contents.push_str(&line);
+                queue!(self.contents, SetAttribute(Attribute::NoReverse))?;
+            } else {
+                self.contents.push_str(&line);
+            }
+
+            queue!(
+                self.contents,
+                terminal::Clear(ClearType::UntilNewLine)
+            )?;
+
+            self.contents.push_str("\r\n");
+        }
+
+        Ok(())
+    }
+
     // find keyword
     pub fn find(&mut self) -> Result<()> {
         let cursor = self.cursor;
@@ -398,6 +1304,8 @@ impl Buffer {
             "↑/↓ search",
             false,
             Self::find_callback,
+            None,
+            Some(PromptKind::Search),
         ).is_none() {
             self.cursor = cursor;
         }
@@ -478,20 +1386,103 @@ impl Buffer {
         }
     }
 
+    // track edit in syntax
+    fn edit_syntax(&self, edit: RowsEdit) {
+        if let Some(it) = &self.syntax {
+            it.edit_rows(&self.rows.rows, edit);
+        }
+    }
+
+    // add row when cursor is past last row
+    fn append_row(&mut self) {
+        let at = self.rows.num_rows();
+
+        if self.cursor.y == at {
+            self.rows.insert_row(at, String::new());
+
+            if at > 0 {
+                // newline after last row
+                let end = (self.rows.get_content(at - 1).len(), at - 1);
+                self.edit_syntax(RowsEdit::insert(end, (0, at), 1));
+            }
+        }
+    }
+
+    // insert pasted text at cursor without auto indent or pairs
+    pub fn paste(&mut self, text: &str) {
+        if self.check_readonly() {
+            return;
+        }
+
+        // pasted text replaces selection
+        let cursor = (self.cursor.x, self.cursor.y);
+        let (start, end) = self.selection().unwrap_or((cursor, cursor));
+
+        self.record(EditKind::Other, start.1..end.1 + 1);
+        self.anchor = None;
+        self.append_row();
+
+        // selection may end past last row
+        let max = self.rows.num_rows() - 1;
+        let end = if end.1 > max { (self.rows.get_content(max).len(), max) } else { end };
+
+        let text  = text.replace("\r\n", "\n").replace('\r', "\n");
+        let lines = text.split('\n').collect::<Vec<&str>>();
+
+        // bytes replaced, counting newlines between rows
+        let old_len = (start.1..end.1)
+            .map(|y| self.rows.get_content(y).len() + 1)
+            .sum::<usize>() + end.0 - start.0;
+
+        // split rows around pasted lines
+        let head = self.rows.get_content(start.1)[..start.0].to_string();
+        let tail = self.rows.get_content(end.1)[end.0..].to_string();
+
+        let last = lines.len() - 1;
+
+        let mut pasted = lines
+            .iter()
+            .map(|line| line.to_string())
+            .collect::<Vec<String>>();
+
+        pasted[0].insert_str(0, &head);
+
+        self.cursor.x = pasted[last].len();
+        self.cursor.y = start.1 + last;
+
+        pasted[last].push_str(&tail);
+
+        self.rows.replace_rows(start.1..end.1 + 1, pasted);
+
+        self.edit_syntax(RowsEdit {
+            start,
+            old_end: end,
+            new_end: (self.cursor.x, self.cursor.y),
+            old_len,
+            new_len: text.len(),
+        });
+
+        self.update_rows(start.1..self.cursor.y + 1);
+
+        self.history.break_group();
+        self.dirty += 1;
+    }
+
     // insert char at cursor
     pub fn insert_char(&mut self, chr: char) {
-        if self.cursor.y == self.rows.num_rows() {
-            self.rows.insert_row(
-                self.rows.num_rows(),
-                String::new(),
-            );
-        }
+        self.append_row();
 
         // get cursor row and insert char
         self.rows
             .get_mut_row(self.cursor.y)
             .insert_char(self.cursor.x, chr);
 
+        self.edit_syntax(RowsEdit::insert(
+            (self.cursor.x, self.cursor.y),
+            (self.cursor.x + chr.len_utf8(), self.cursor.y),
+            chr.len_utf8(),
+        ));
+
         if let Some(it) = &self.syntax {
             it.update_syntax(
                 self.cursor.y,
@@ -505,16 +1496,33 @@ impl Buffer {
 
     // insert char at cursor
     pub fn delete_char(&mut self) {
+        if self.check_readonly() {
+            return;
+        }
+
         // prevent deleting first line
         if self.cursor.x == 0 && self.cursor.y == 0 {
             return;
         }
 
-        if self.cursor.y == self.rows.num_rows() {
-            self.rows.insert_row(
-                self.rows.num_rows(),
-                String::new(),
-            );
+        // backspace at row start joins previous row
+        let start = if self.cursor.x == 0 { self.cursor.y - 1 } else { self.cursor.y };
+        self.record(EditKind::Delete, start..self.cursor.y + 1);
+
+        self.anchor = None;
+        self.append_row();
+
+        // delete closer of empty pair
+        if self.in_empty_pair() {
+            self.rows
+                .get_mut_row(self.cursor.y)
+                .delete_char(self.cursor.x);
+
+            self.edit_syntax(RowsEdit::delete(
+                (self.cursor.x, self.cursor.y),
+                (self.cursor.x + 1, self.cursor.y),
+                1,
+            ));
         }
 
         // get cursor row and delete char
@@ -522,13 +1530,28 @@ impl Buffer {
 
         if self.cursor.x == 0 {
             let prev_row = self.rows.get_content(self.cursor.y - 1);
-            self.cursor.x = prev_row.len();
+            let prev_len = prev_row.len();
 
             // join lines when deleting first char
             self.rows.join_adjacent_rows(self.cursor.y);
+
+            self.edit_syntax(RowsEdit::delete(
+                (prev_len, self.cursor.y - 1),
+                (0, self.cursor.y),
+                1,
+            ));
+
+            self.cursor.x = prev_len;
             self.cursor.y -= 1;
         } else {
             row.delete_char(self.cursor.x - 1);
+
+            self.edit_syntax(RowsEdit::delete(
+                (self.cursor.x - 1, self.cursor.y),
+                (self.cursor.x, self.cursor.y),
+                1,
+            ));
+
             self.cursor.x -= 1;
         }
 
@@ -544,12 +1567,41 @@ impl Buffer {
 
     // insert newline
     pub fn insert_newline(&mut self) {
+        if self.check_readonly() {
+            return;
+        }
+
         // offset of indented contents
         let mut indent_offset = 0;
 
+        self.record(EditKind::Other, self.cursor.y..self.cursor.y + 1);
+        self.anchor = None;
+
         if self.cursor.x == 0 {
-            self.rows.insert_row(self.cursor.y, String::new());
+            if self.cursor.y == self.rows.num_rows() {
+                self.append_row();
+            } else {
+                self.rows.insert_row(self.cursor.y, String::new());
+
+                self.edit_syntax(RowsEdit::insert(
+                    (0, self.cursor.y),
+                    (0, self.cursor.y + 1),
+                    1,
+                ));
+            }
+
+            if let Some(it) = &self.syntax {
+                it.update_syntax(
+                    self.cursor.y,
+                    &mut self.rows.rows,
+                );
+            }
         } else {
+            // cursor is between bracket pair
+            let between_pair = Config::get_config().indent.auto && BRACKETS
+                .iter()
+                .any(|&pair| (self.char_at(-1), self.char_at(0)) == (Some(pair.0), Some(pair.1)));
+
             // split current row into two rows
             let curr_row = self.rows.get_mut_row(self.cursor.y);
             let new_content = curr_row.content[self.cursor.x..].to_string();
@@ -563,22 +1615,39 @@ impl Buffer {
                 .auto_indent(
                     self.cursor.y + 1,
                     &new_content,
+                    &self.indent_triggers(),
                 );
 
             indent_offset = indented.len() - new_content.len();
             self.rows.insert_row(self.cursor.y + 1, indented);
 
-            if let Some(it) = &self.syntax {
-                it.update_syntax(
-                    self.cursor.y,
-                    &mut self.rows.rows,
-                );
-
-                it.update_syntax(
-                    self.cursor.y + 1,
-                    &mut self.rows.rows,
-                );
+            self.edit_syntax(RowsEdit::insert(
+                (self.cursor.x, self.cursor.y),
+                (indent_offset, self.cursor.y + 1),
+                indent_offset + 1,
+            ));
+
+            // move closer of block to own row
+            if between_pair {
+                let base = self.rows.indentation(self.cursor.y).to_string();
+                let closer = format!("{}{}", base, &new_content);
+
+                self.rows
+                    .get_mut_row(self.cursor.y + 1)
+                    .content
+                    .truncate(indent_offset);
+
+                Rows::render_row(self.rows.get_mut_row(self.cursor.y + 1));
+                self.rows.insert_row(self.cursor.y + 2, closer);
+
+                self.edit_syntax(RowsEdit::insert(
+                    (indent_offset, self.cursor.y + 1),
+                    (base.len(), self.cursor.y + 2),
+                    base.len() + 1,
+                ));
             }
+
+            self.update_rows(self.cursor.y..self.cursor.y + 2 + between_pair as usize);
         }
 
         self.cursor.x  = indent_offset;
@@ -608,18 +1677,24 @@ impl Buffer {
         let line_nums_width = self.rows.line_nums_width();
 
         // get cursor x
-        let cursor_x = (
+        let mut cursor_x = (
             self.cursor.render_width -
             self.cursor.col_offset +
             line_nums_width
         ) as u16;
 
         // get cursor y
-        let cursor_y = (
+        let mut cursor_y = (
             self.cursor.y -
             self.cursor.row_offset + 1
         ) as u16;
 
+        // place cursor on selected overlay item
+        if let Some(overlay) = &self.overlay {
+            cursor_x = 0;
+            cursor_y = (overlay.selected_line(self.term_size.1 - 1) + 1) as u16;
+        }
+
         // update cursor position
         queue!(
             self.contents,
@@ -0,0 +1,177 @@
+use shellexpand::tilde;
+
+use std::fs;
+
+// candidates for last component of input
+pub struct Completion {
+    // input before completed component
+    base: String,
+
+    // matching names, directories ending with slash
+    names: Vec<String>,
+}
+
+impl Completion {
+    // completion with no candidates
+    fn none() -> Self {
+        Self {
+            base:  String::new(),
+            names: Vec::new(),
+        }
+    }
+
+    // longest prefix shared by all names
+    fn common_prefix(&self) -> &str {
+        let first = &self.names[0];
+
+        let len = self.names
+            .iter()
+            .map(|name| {
+                first
+                    .char_indices()
+                    .zip(name.chars())
+                    .find(|((_, a), b)| a != b)
+                    .map_or(first.len().min(name.len()), |((i, _), _)| i)
+            })
+            .min()
+            .unwrap_or(0);
+
+        &first[..len]
+    }
+}
+
+// tab completion state for prompt
+pub struct Completer {
+    // function getting candidates for input
+    complete: fn(&str) -> Completion,
+
+    // candidates being cycled through
+    current: Option<Completion>,
+
+    // selected candidate
+    selected: Option<usize>,
+}
+
+impl Completer {
+    // create completer using function
+    pub fn new(complete: fn(&str) -> Completion) -> Self {
+        Self {
+            complete,
+            current:  None,
+            selected: None,
+        }
+    }
+
+    // complete input, cycling through candidates when repeated
+    pub fn complete(&mut self, input: &mut String) {
+        if let Some(current) = &self.current {
+            let idx = self.selected.map_or(0, |i| (i + 1) % current.names.len());
+
+            *input = format!("{}{}", current.base, current.names[idx]);
+            self.selected = Some(idx);
+
+            return;
+        }
+
+        let completion = (self.complete)(input);
+
+        match completion.names.len() {
+            0 => {}
+
+            // complete single candidate
+            1 => *input = format!("{}{}", completion.base, completion.names[0]),
+
+            // extend to shared prefix and list candidates
+            _ => {
+                *input = format!("{}{}", completion.base, completion.common_prefix());
+                self.current = Some(completion);
+            }
+        }
+    }
+
+    // stop cycling through candidates
+    pub fn reset(&mut self) {
+        self.current  = None;
+        self.selected = None;
+    }
+
+    // get candidates to show, marking selected
+    pub fn candidates(&self) -> Option<String> {
+        let current = self.current.as_ref()?;
+
+        let names = current.names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                if self.selected == Some(i) { format!("[{}]", name) }
+                else { name.clone() }
+            })
+            .collect::<Vec<String>>();
+
+        Some(names.join(" "))
+    }
+}
+
+// complete filesystem path
+pub fn path(input: &str) -> Completion {
+    // expand bare tilde to home
+    if input == "~" {
+        return Completion {
+            base:  String::new(),
+            names: vec![String::from("~/")],
+        };
+    }
+
+    let (base, prefix) = match input.rfind('/') {
+        Some(idx) => input.split_at(idx + 1),
+        None => ("", input),
+    };
+
+    let dir = if base.is_empty() { String::from(".") } else { tilde(base).into_owned() };
+
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Completion::none(),
+    };
+
+    let mut names = entries
+        .flatten()
+        .filter_map(|entry| {
+            let mut name = entry.file_name().into_string().ok()?;
+
+            // hide dotfiles unless typed
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+
+            if entry.path().is_dir() {
+                name.push('/');
+            }
+
+            Some(name)
+        })
+        .collect::<Vec<String>>();
+
+    names.sort();
+
+    Completion {
+        base: String::from(base),
+        names,
+    }
+}
+
+// complete path arguments of editor commands
+pub fn command(input: &str) -> Completion {
+    match input.strip_prefix("open ") {
+        Some(arg) => {
+            let completion = path(arg);
+
+            Completion {
+                base: format!("open {}", completion.base),
+                ..completion
+            }
+        }
+
+        None => Completion::none(),
+    }
+}
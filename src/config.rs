@@ -5,13 +5,22 @@ use serde::Deserialize;
 
 use std::fs;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
 // main config struct
 pub struct Config;
 
+// config read on first use
+static CONFIG: OnceLock<ConfigFile> = OnceLock::new();
+
 impl Config {
-    // get config from config file
-    pub fn get_config() -> ConfigFile {
+    // get config, reading config file once
+    pub fn get_config() -> &'static ConfigFile {
+        CONFIG.get_or_init(Self::read_config)
+    }
+
+    // read config from config file
+    fn read_config() -> ConfigFile {
         let path = PathBuf::from(&*tilde("~/.ferrite.toml"));
 
         // read from config file or use defaults
@@ -37,6 +46,21 @@ pub struct ConfigFile {
 
     #[serde(default)]
     pub indent: IndentTable,
+
+    #[serde(default)]
+    pub pairs: PairsTable,
+
+    #[serde(default)]
+    pub files: FilesTable,
+
+    #[serde(default)]
+    pub swap: SwapTable,
+
+    #[serde(default)]
+    pub autosave: AutosaveTable,
+
+    #[serde(default)]
+    pub session: SessionTable,
 }
 
 // cursor config table
@@ -93,33 +117,138 @@ impl Default for IndentTable {
     }
 }
 
+// pairs config table
+#[derive(Deserialize)]
+pub struct PairsTable {
+    #[serde(default = "default_true")]
+    pub auto: bool,
+}
+
+// use serde defaults for impl default
+impl Default for PairsTable {
+    fn default() -> Self {
+        from_str("").unwrap()
+    }
+}
+
+// files config table
+#[derive(Deserialize)]
+pub struct FilesTable {
+    #[serde(default)]
+    pub create_dirs: bool,
+
+    #[serde(default)]
+    pub ensure_final_newline: bool,
+
+    #[serde(default)]
+    pub line_ending: LineEnding,
+
+    #[serde(default)]
+    pub backup: bool,
+
+    #[serde(default)]
+    pub auto_reload: bool,
+
+    #[serde(default)]
+    pub persistent_undo: bool,
+}
+
+// use serde defaults for impl default
+impl Default for FilesTable {
+    fn default() -> Self {
+        from_str("").unwrap()
+    }
+}
+
+// swap config table
+#[derive(Deserialize)]
+pub struct SwapTable {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    #[serde(default = "default_swap_interval")]
+    pub interval: u64,
+}
+
+// use serde defaults for impl default
+impl Default for SwapTable {
+    fn default() -> Self {
+        from_str("").unwrap()
+    }
+}
+
+// autosave config table
+#[derive(Deserialize)]
+pub struct AutosaveTable {
+    #[serde(default)]
+    pub enabled: bool,
+
+    #[serde(default = "default_autosave_idle")]
+    pub idle: u64,
+
+    #[serde(default = "default_true")]
+    pub on_switch: bool,
+
+    #[serde(default = "default_true")]
+    pub on_focus_lost: bool,
+}
+
+// use serde defaults for impl default
+impl Default for AutosaveTable {
+    fn default() -> Self {
+        from_str("").unwrap()
+    }
+}
+
+// session config table
+#[derive(Deserialize)]
+pub struct SessionTable {
+    #[serde(default)]
+    pub restore: bool,
+}
+
+// use serde defaults for impl default
+impl Default for SessionTable {
+    fn default() -> Self {
+        from_str("").unwrap()
+    }
+}
+
 // defaults for serde
 fn default_four() -> usize { 4 }
 fn default_true() -> bool  { true }
 fn default_tab_char() -> char { '»' }
+fn default_swap_interval() -> u64 { 4 }
+fn default_autosave_idle() -> u64 { 30 }
 
 // cursor config shape
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum CursorShape {
+    // default cursor shape
+    #[default]
     Block,
     Line,
     Underscore,
 }
 
 impl CursorShape {
-    pub fn to_crossterm(&self) -> cursor::CursorShape {
+    pub fn to_crossterm(&self) -> cursor::SetCursorStyle {
         match self {
-            Self::Block      => cursor::CursorShape::Block,
-            Self::Line       => cursor::CursorShape::Line,
-            Self::Underscore => cursor::CursorShape::UnderScore,
+            Self::Block      => cursor::SetCursorStyle::SteadyBlock,
+            Self::Line       => cursor::SetCursorStyle::SteadyBar,
+            Self::Underscore => cursor::SetCursorStyle::SteadyUnderScore,
         }
     }
 }
 
-// default cursor shape
-impl Default for CursorShape {
-    fn default() -> Self {
-        CursorShape::Block
-    }
+// line ending used on save
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    // keep line ending of file
+    #[default]
+    Auto,
+    Lf,
+    Crlf,
 }
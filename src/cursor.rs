@@ -20,6 +20,12 @@ pub struct Cursor {
 
     // row render width
     pub render_width: usize,
+
+    // column kept when moving vertically, with column it was snapped to
+    desired_x: Option<(usize, usize)>,
+
+    // center cursor on next scroll
+    center: bool,
 }
 
 impl Cursor {
@@ -33,6 +39,8 @@ impl Cursor {
             row_offset: 0,
             col_offset: 0,
             render_width: 0,
+            desired_x: None,
+            center: false,
         }
     }
 
@@ -40,6 +48,20 @@ impl Cursor {
     pub fn move_cursor(&mut self, dir: KeyCode, rows: &Rows) {
         let num_rows = rows.num_rows();
 
+        // remember column for vertical movement
+        let vertical = matches!(
+            dir,
+            KeyCode::Up | KeyCode::Down | KeyCode::PageUp | KeyCode::PageDown,
+        );
+
+        // forget column if cursor moved otherwise
+        let desired_x = match self.desired_x {
+            Some((desired, x)) if vertical && x == self.x => desired,
+            _ => self.x,
+        };
+
+        self.x = desired_x;
+
         match dir {
             KeyCode::Up => {
                 self.y = self.y.saturating_sub(1);
@@ -55,30 +77,47 @@ impl Cursor {
                 }
             }
 
-            KeyCode::Down => {
-                if self.y < num_rows {
-                    self.y += 1;
-                }
+            KeyCode::Down if self.y < num_rows => {
+                self.y += 1;
             }
 
-            KeyCode::Right => {
-                if self.y < num_rows {
-                    let row_len = rows.get_content(self.y).len();
-
-                    match self.x.cmp(&row_len) {
-                        Ordering::Less => self.x += 1,
+            KeyCode::Right if self.y < num_rows => {
+                let row_len = rows.get_content(self.y).len();
 
-                        Ordering::Equal => {
-                            // go to start of next row
-                            self.y += 1;
-                            self.x = 0;
-                        }
+                match self.x.cmp(&row_len) {
+                    Ordering::Less => self.x += 1,
 
-                        _ => {}
+                    Ordering::Equal => {
+                        // go to start of next row
+                        self.y += 1;
+                        self.x = 0;
                     }
+
+                    _ => {}
                 }
             }
 
+            KeyCode::Home if self.y < num_rows => {
+                let indent = rows.indentation(self.y).len();
+
+                // toggle between first non-blank and row start
+                self.x =
+                    if self.x == indent { 0 }
+                    else { indent };
+            }
+
+            KeyCode::End if self.y < num_rows => {
+                self.x = rows.get_content(self.y).len();
+            }
+
+            KeyCode::PageUp => {
+                self.y = self.y.saturating_sub(self.rows);
+            }
+
+            KeyCode::PageDown => {
+                self.y = min(self.y + self.rows, num_rows);
+            }
+
             _ => {}
         }
 
@@ -90,12 +129,104 @@ impl Cursor {
         };
 
         self.x = min(self.x, row_len);
+
+        self.desired_x =
+            if vertical { Some((desired_x, self.x)) }
+            else { None };
+    }
+
+    // move cursor by word
+    pub fn move_word(&mut self, forward: bool, rows: &Rows, is_separator: impl Fn(char) -> bool) {
+        self.desired_x = None;
+
+        if self.y >= rows.num_rows() {
+            return;
+        }
+
+        let content = rows.get_content(self.y);
+
+        if forward {
+            if self.x >= content.len() {
+                // go to start of next row
+                if self.y + 1 < rows.num_rows() {
+                    self.y += 1;
+                    self.x = 0;
+                }
+
+                return;
+            }
+
+            let mut chars = content[self.x..]
+                .char_indices()
+                .map(|(i, c)| (i + self.x, c))
+                .peekable();
+
+            // skip rest of word, then separators
+            while chars.next_if(|&(_, c)| !is_separator(c)).is_some() {}
+            while chars.next_if(|&(_, c)| is_separator(c)).is_some() {}
+
+            self.x = chars
+                .peek()
+                .map_or(content.len(), |&(i, _)| i);
+        } else {
+            if self.x == 0 {
+                // go to end of previous row
+                if self.y > 0 {
+                    self.y -= 1;
+                    self.x = rows.get_content(self.y).len();
+                }
+
+                return;
+            }
+
+            let mut chars = content[..self.x]
+                .char_indices()
+                .rev()
+                .peekable();
+
+            // skip separators, then word
+            while let Some((i, _)) = chars.next_if(|&(_, c)| is_separator(c)) {
+                self.x = i;
+            }
+
+            while let Some((i, _)) = chars.next_if(|&(_, c)| !is_separator(c)) {
+                self.x = i;
+            }
+        }
+    }
+
+    // move cursor to start or end of document
+    pub fn move_document(&mut self, end: bool, rows: &Rows) {
+        self.desired_x = None;
+
+        if end && rows.num_rows() > 0 {
+            self.y = rows.num_rows() - 1;
+            self.x = rows.get_content(self.y).len();
+        } else {
+            self.x = 0;
+            self.y = 0;
+        }
+    }
+
+    // jump to position and center view
+    pub fn goto(&mut self, x: usize, y: usize) {
+        self.x = x;
+        self.y = y;
+
+        self.desired_x = None;
+        self.center = true;
     }
 
     // scroll editor
     pub fn scroll(&mut self, rows: &Rows) {
         self.render_width = 0;
 
+        // center row in view
+        if self.center {
+            self.row_offset = self.y.saturating_sub(self.rows / 2);
+            self.center = false;
+        }
+
         if self.y < rows.num_rows() {
             // set row width
             self.render_width = self.get_render_width(rows.get_row(self.y));
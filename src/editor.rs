@@ -1,15 +1,30 @@
 use crate::utils::prompt;
+use crate::args::{Args, FileArg};
 use crate::buffer::Buffer;
 use crate::config::Config;
-use crate::reader::Reader;
+use crate::reader::{Input, Reader};
+use crate::rows::Rows;
+use crate::swap::Swap;
+use crate::session::{Session, SessionBuffer};
+use crate::positions::{FilePosition, Positions};
+use crate::recent::Recent;
+use crate::picker;
+use crate::finder;
+use crate::complete;
+use crate::state;
 
 use crossterm::event::{KeyCode, KeyModifiers, KeyEvent};
-use crossterm::Result;
 
 use shellexpand::tilde;
 
-use std::env::args;
+use std::env;
+use std::fs;
+use std::mem;
+use std::path;
+use std::process;
+use std::io::{Write, Error, Result};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
 
 pub struct Editor {
     // buffers
@@ -17,15 +32,540 @@ pub struct Editor {
 
     // current buffer
     buffer: usize,
+
+    // time of last keypress
+    last_input: Instant,
+
+    // open files as readonly
+    readonly: bool,
+
+    // files listed by finder for directory and its modified time
+    files: Option<(PathBuf, Option<SystemTime>, Vec<String>)>,
 }
 
 impl Editor {
     // create editor
-    pub fn new() -> Self {
-        Self {
-            buffers: vec![Buffer::new(args().nth(1))],
-            buffer: 0,
+    pub fn new(args: &Args) -> Result<Self> {
+        let mut editor = Self {
+            buffers:    Vec::new(),
+            buffer:     0,
+            last_input: Instant::now(),
+            readonly:   args.readonly,
+            files:      None,
+        };
+
+        let mut errors = Vec::new();
+
+        // restore session before opening files
+        let session = match &args.session {
+            Some(name) => {
+                Some(Session::load(name).map_err(|err| {
+                    format!("[error] unable to load session `{}`: {}", name, err)
+                }))
+            }
+
+            None if args.files.is_empty() && Config::get_config().session.restore => {
+                Session::load_dir().ok().map(Ok)
+            }
+
+            None => None,
+        };
+
+        match session {
+            Some(Ok(session)) => editor.open_session(&session, &mut errors),
+            Some(Err(err)) => errors.push(err),
+            None => {}
+        }
+
+        for arg in &args.files {
+            let buffer = match &arg.file {
+                FileArg::Path(path) => {
+                    Buffer::new(Some(path.clone()))
+                        .map_err(|err| Self::open_error(path, err))
+                }
+
+                FileArg::Stdin(contents) => {
+                    Buffer::from_contents(contents)
+                        .map_err(|err| Self::open_error("-", err))
+                }
+            };
+
+            match buffer {
+                Ok(mut buffer) => {
+                    match &arg.position {
+                        Some(pos) => buffer.goto(pos),
+                        None => Self::restore_position(&mut buffer),
+                    }
+
+                    buffer.readonly |= editor.readonly;
+                    editor.buffers.push(buffer);
+                }
+
+                Err(err) => errors.push(err),
+            }
         }
+
+        if editor.buffers.is_empty() {
+            editor.buffers.push(Buffer::new(None)?);
+        }
+
+        // show errors for files that could not be opened
+        if !errors.is_empty() {
+            editor.buffers[editor.buffer].message.set_message(errors.join(", "));
+        }
+
+        editor.check_swaps(0)?;
+        editor.check_unnamed_swaps()?;
+
+        Ok(editor)
+    }
+
+    // offer recovery for buffers from index
+    fn check_swaps(&mut self, from: usize) -> Result<()> {
+        let current = self.buffer;
+
+        for idx in from..self.buffers.len() {
+            self.check_swap(idx)?;
+        }
+
+        self.buffer = current;
+        Ok(())
+    }
+
+    // restore remembered cursor position
+    fn restore_position(buffer: &mut Buffer) {
+        let positions = Positions::load();
+
+        let position = buffer.rows.filepath
+            .as_ref()
+            .and_then(|path| positions.get(path));
+
+        if let Some(position) = position {
+            buffer.set_position(position.cursor, position.offset);
+        }
+    }
+
+    // remember cursor positions of buffers
+    fn remember_positions<'a>(buffers: impl IntoIterator<Item = &'a Buffer>) {
+        let mut positions = Positions::load();
+
+        for buffer in buffers {
+            if let Some(path) = &buffer.rows.filepath {
+                let (cursor, offset) = buffer.position();
+
+                positions.remember(FilePosition {
+                    path: path.clone(),
+                    cursor,
+                    offset,
+                });
+            }
+        }
+
+        let _ = positions.save();
+    }
+
+    // open buffers from session
+    fn open_session(&mut self, session: &Session, errors: &mut Vec<String>) {
+        for (i, saved) in session.buffers.iter().enumerate() {
+            let path = saved.path.to_string_lossy().into_owned();
+
+            match Buffer::new(Some(path.clone())) {
+                Ok(mut buffer) => {
+                    buffer.set_position(saved.cursor, saved.offset);
+                    buffer.readonly |= self.readonly;
+
+                    if i == session.current {
+                        self.buffer = self.buffers.len();
+                    }
+
+                    self.buffers.push(buffer);
+                }
+
+                Err(err) => errors.push(Self::open_error(&path, err)),
+            }
+        }
+    }
+
+    // get session for open buffers
+    fn session(&self) -> Session {
+        let mut session = Session::default();
+
+        for (i, buffer) in self.buffers.iter().enumerate() {
+            // unnamed buffers cannot be reopened
+            let path = match &buffer.rows.filepath {
+                Some(path) => path,
+                None => continue,
+            };
+
+            if i == self.buffer {
+                session.current = session.buffers.len();
+            }
+
+            let (cursor, offset) = buffer.position();
+
+            session.buffers.push(SessionBuffer {
+                path: path::absolute(path).unwrap_or_else(|_| path.clone()),
+                cursor,
+                offset,
+            });
+        }
+
+        session
+    }
+
+    // replace buffers with named session
+    fn load_session(&mut self, name: &str) -> Result<()> {
+        if self.buffers.iter().any(|buffer| buffer.dirty > 0) {
+            self.buffers[self.buffer]
+                .message
+                .set_message(String::from(
+                    "[warning] buffers have unsaved changes. save them before loading a session.",
+                ));
+
+            return Ok(());
+        }
+
+        let session = match Session::load(name) {
+            Ok(session) => session,
+
+            Err(err) => {
+                self.buffers[self.buffer]
+                    .message
+                    .set_message(format!("[error] unable to load session `{}`: {}", name, err));
+
+                return Ok(());
+            }
+        };
+
+        let old_buffers = mem::take(&mut self.buffers);
+        let old_buffer  = self.buffer;
+
+        let mut errors = Vec::new();
+
+        self.buffer = 0;
+        self.open_session(&session, &mut errors);
+
+        // keep buffers if nothing could be opened
+        if self.buffers.is_empty() {
+            self.buffers = old_buffers;
+            self.buffer  = old_buffer;
+
+            errors.insert(0, format!("[error] no files opened from session `{}`", name));
+        } else {
+            Self::remember_positions(&old_buffers);
+        }
+
+        let message =
+            if errors.is_empty() { format!("session `{}` loaded", name) }
+            else { errors.join(", ") };
+
+        self.buffers[self.buffer].message.set_message(message);
+        self.check_swaps(0)
+    }
+
+    // save buffers as named session
+    fn save_session(&mut self, name: &str) {
+        let message = match self.session().save(name) {
+            Ok(_) => format!("session `{}` saved", name),
+            Err(err) => format!("[error] unable to save session `{}`: {}", name, err),
+        };
+
+        self.buffers[self.buffer].message.set_message(message);
+    }
+
+    // save state of buffers on clean exit
+    pub fn close(&mut self) {
+        Self::remember_positions(&self.buffers);
+        self.save_dir_session();
+
+        for buffer in &mut self.buffers {
+            buffer.save_history();
+        }
+
+        // nothing left to recover
+        self.remove_swaps();
+    }
+
+    // save session for working directory if enabled
+    fn save_dir_session(&self) {
+        if Config::get_config().session.restore {
+            let _ = self.session().save_dir();
+        }
+    }
+
+    // offer recovery of swap left by previous session
+    fn check_swap(&mut self, idx: usize) -> Result<()> {
+        let path = match self.buffers[idx].rows.filepath.clone() {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        let stale = match Swap::read_stale(&path) {
+            Some(stale) => stale,
+            None => return Ok(()),
+        };
+
+        let running = stale.running();
+        let swap = stale.contents;
+
+        // nothing to recover if swap matches file
+        if !running && swap == self.buffers[idx].rows.contents().join("\n") {
+            Swap::remove_stale(&path);
+            return Ok(());
+        }
+
+        // warn when file is still being edited elsewhere
+        let message = match stale.pid.filter(|_| running) {
+            Some(pid) => format!("swap file in use by process {}. (r)ecover, (d)iscard, di(f)f", pid),
+            None => String::from("swap file found. (r)ecover, (d)iscard, di(f)f"),
+        };
+
+        let message: &str = &message;
+
+        // buffer showing prompt
+        let mut shown = idx;
+
+        loop {
+            let input = prompt!(self.buffers[shown], message);
+
+            match input.as_deref() {
+                Some("r" | "recover") => {
+                    self.buffers[idx].recover(&swap);
+                    Swap::remove_stale(&path);
+                    break;
+                }
+
+                Some("d" | "discard") => {
+                    Swap::remove_stale(&path);
+                    break;
+                }
+
+                Some("f" | "diff") => {
+                    // write swap lines like file on save
+                    let text = self.buffers[idx].rows.join_lines(swap.split('\n'));
+
+                    let diff = Rows::diff_file(&path, &text)
+                        .and_then(|diff| Buffer::from_contents(&diff));
+
+                    match diff {
+                        Ok(mut buffer) => {
+                            buffer.dirty    = 0;
+                            buffer.readonly = true;
+
+                            self.buffers.push(buffer);
+                            shown = self.buffers.len() - 1;
+                        }
+
+                        Err(err) => {
+                            self.buffers[shown]
+                                .message
+                                .set_message(format!("[error] unable to diff swap: {}", err));
+                        }
+                    }
+                }
+
+                // keep swap for next time
+                _ => break,
+            }
+        }
+
+        self.buffer = idx;
+        Ok(())
+    }
+
+    // offer recovery of unnamed buffers from exited sessions
+    fn check_unnamed_swaps(&mut self) -> Result<()> {
+        for (path, stale) in Swap::unnamed_stale() {
+            let input = prompt!(self.buffers[self.buffer], "unnamed swap file found. (r)ecover, (d)iscard");
+
+            match input.as_deref() {
+                Some("r" | "recover") => {
+                    let mut buffer = Buffer::new(None)?;
+                    buffer.recover(&stale.contents);
+
+                    self.buffers.push(buffer);
+                    self.buffer = self.buffers.len() - 1;
+
+                    let _ = fs::remove_file(path);
+                }
+
+                Some("d" | "discard") => {
+                    let _ = fs::remove_file(path);
+                }
+
+                // keep swap for next time
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    // remove swaps of all buffers
+    fn remove_swaps(&mut self) {
+        for buffer in &mut self.buffers {
+            buffer.swap.remove();
+        }
+    }
+
+    // write copies of dirty buffers after crash
+    pub fn write_recovery(&self) -> Vec<std::result::Result<PathBuf, String>> {
+        let dir = match state::state_dir("recovery") {
+            Ok(dir) => dir,
+            Err(err) => return vec![Err(format!("unable to create recovery directory: {}", err))],
+        };
+
+        self.buffers
+            .iter()
+            .enumerate()
+            .filter(|(_, buffer)| buffer.dirty > 0)
+            .map(|(i, buffer)| {
+                let name = match &buffer.rows.filepath {
+                    Some(path) => state::escape_path(path),
+                    None => format!("no-name-{}", i + 1),
+                };
+
+                // include pid to keep earlier copies
+                let path = dir.join(format!("{}.{}", name, process::id()));
+
+                // write like file on save, keeping unencodable text as utf-8
+                let bytes = buffer.rows.encoded().unwrap_or_else(|_| {
+                    buffer.rows
+                        .join_lines(buffer.rows.rows.iter().map(|it| it.content.as_str()))
+                        .into_bytes()
+                });
+
+                state::create_private(&path)
+                    .and_then(|mut file| file.write_all(&bytes))
+                    .map(|_| path)
+                    .map_err(|err| format!("unable to write `{}`: {}", name, err))
+            })
+            .collect()
+    }
+
+    // write swaps of changed buffers
+    fn update_swaps(&mut self) {
+        for buffer in &mut self.buffers {
+            buffer.swap.update(&buffer.rows, buffer.dirty);
+        }
+    }
+
+    // open diff against file on disk as new buffer
+    fn open_diff(&mut self) {
+        let diff = self.buffers[self.buffer]
+            .rows
+            .diff_with_disk()
+            .and_then(|diff| Buffer::from_contents(&diff));
+
+        match diff {
+            Ok(mut buffer) => {
+                buffer.dirty    = 0;
+                buffer.readonly = true;
+
+                self.buffers.push(buffer);
+                self.buffer = self.buffers.len() - 1;
+            }
+
+            Err(err) => {
+                self.buffers[self.buffer]
+                    .message
+                    .set_message(format!("[error] unable to diff file: {}", err));
+            }
+        }
+    }
+
+    // reload unmodified buffers changed on disk, returning if any reloaded
+    fn auto_reload(&mut self) -> bool {
+        if !Config::get_config().files.auto_reload {
+            return false;
+        }
+
+        let mut reloaded = false;
+
+        for buffer in &mut self.buffers {
+            if buffer.dirty == 0 && buffer.rows.changed_on_disk() {
+                buffer.reload();
+                reloaded = true;
+            }
+        }
+
+        reloaded
+    }
+
+    // save dirty buffers with file paths, returning if any saved
+    fn autosave(&mut self, buffers: &[usize]) -> bool {
+        let mut saved   = Vec::new();
+        let mut skipped = Vec::new();
+
+        for &idx in buffers {
+            let buffer = &mut self.buffers[idx];
+
+            if buffer.dirty == 0 || buffer.readonly {
+                continue;
+            }
+
+            let name = match &buffer.rows.filepath {
+                Some(path) => path.display().to_string(),
+                None => continue,
+            };
+
+            // leave external changes for save prompt
+            if buffer.rows.changed_on_disk() {
+                skipped.push(name);
+                continue;
+            }
+
+            match buffer.rows.write_file() {
+                Ok(_) => {
+                    buffer.dirty = 0;
+                    buffer.swap.remove();
+                    buffer.save_history();
+
+                    saved.push(name);
+                }
+
+                Err(err) => {
+                    buffer.message.set_message(format!(
+                        "[error] unable to autosave file: {}",
+                        err,
+                    ));
+                }
+            }
+        }
+
+        let message = &mut self.buffers[self.buffer].message;
+
+        // skipped until conflict resolved from save prompt
+        if !skipped.is_empty() {
+            message.set_message(format!(
+                "[warning] not autosaved, changed on disk: {}. save to resolve",
+                skipped.join(", "),
+            ));
+        } else if !saved.is_empty() {
+            message.set_message(format!("autosaved {}", saved.join(", ")));
+        }
+
+        !saved.is_empty() || !skipped.is_empty()
+    }
+
+    // autosave after idle time, returning if any saved
+    fn autosave_idle(&mut self) -> bool {
+        let config = &Config::get_config().autosave;
+
+        if !config.enabled || self.last_input.elapsed() < Duration::from_secs(config.idle) {
+            return false;
+        }
+
+        // wait another idle period before retrying
+        self.last_input = Instant::now();
+
+        let all = (0..self.buffers.len()).collect::<Vec<_>>();
+        self.autosave(&all)
+    }
+
+    // message for file open error
+    fn open_error(path: &str, err: Error) -> String {
+        format!("[error] unable to open `{}`: {}", path, err)
     }
 
     // quit whole editor
@@ -60,7 +600,11 @@ impl Editor {
         }
 
         if self.buffers.len() > 1 {
-            self.buffers.remove(self.buffer);
+            let mut buffer = self.buffers.remove(self.buffer);
+
+            Self::remember_positions([&buffer]);
+            buffer.swap.remove();
+            buffer.save_history();
 
             self.buffer =
                 if self.buffer > self.buffers.len() - 1 {
@@ -80,85 +624,279 @@ impl Editor {
     fn write_file(&mut self, prompt: bool) -> Result<()> {
         let mut buffer = &mut self.buffers[self.buffer];
 
+        if buffer.readonly {
+            buffer.message.set_message(String::from("[warning] buffer is read-only"));
+            return Ok(());
+        }
+
         // prompt for path if filepath is none
-        if prompt || buffer.rows.filepath.is_none() {
-            let input = prompt!(&mut buffer, "save as");
+        let prompt = prompt || buffer.rows.filepath.is_none();
+
+        if prompt {
+            let input = prompt!(&mut buffer, "save as", complete::path);
 
             if let Some(p) = input {
-                let path = &*tilde(&p);
-                buffer.rows.filepath = Some(PathBuf::from(path));
-
-                let path: &Path = path.as_ref();
-                path.extension()
-                    .and_then(|ext| ext.to_str())
-                    .map(|ext| {
-                        // update syntax
-                        Buffer::get_syntax(ext).map(|syntax| {
-                            let highlight = buffer.syntax.insert(syntax);
-
-                            for i in 0..buffer.rows.num_rows() {
-                                highlight.update_syntax(i, &mut buffer.rows.rows);
-                            }
-                        })
-                    });
+                let path = PathBuf::from(&*tilde(&p));
+
+                // update syntax
+                if let Some(syntax) = Buffer::syntax_for_path(&path) {
+                    buffer.syntax
+                        .insert(syntax)
+                        .update_all(&mut buffer.rows.rows);
+                }
+
+                buffer.rows.filepath = Some(path);
             } else {
                 return Ok(());
             }
         }
 
+        // confirm before overwriting external changes
+        if !prompt && buffer.rows.changed_on_disk() {
+            let input = prompt!(&mut buffer, "file changed on disk. (r)eload, (k)eep and save, (d)iff");
+
+            match input.as_deref() {
+                Some("k" | "keep") => {}
+
+                Some("r" | "reload") => {
+                    buffer.reload();
+                    return Ok(());
+                }
+
+                Some("d" | "diff") => {
+                    self.open_diff();
+                    return Ok(());
+                }
+
+                _ => return Ok(()),
+            }
+        }
+
+        // new files are missing from finder list
+        let created = buffer.rows.filepath
+            .as_ref()
+            .is_some_and(|path| !path.exists());
+
         // write file and show message
-        buffer.rows.write_file().map(|len| {
-            buffer.message.set_message(format!(
-                "{} bytes written to {}",
-                len,
+        match buffer.rows.write_file() {
+            Ok(len) => {
+                if created {
+                    self.files = None;
+                }
+
+                buffer.message.set_message(format!(
+                    "{} bytes written to {}",
+                    len,
+                    buffer.rows.filepath
+                        .clone().unwrap().display(),
+                ));
+
+                buffer.dirty = 0;
+                buffer.swap.remove();
+                buffer.save_history();
+            }
+
+            Err(err) => {
+                buffer.message.set_message(format!(
+                    "[error] unable to write file: {}",
+                    err,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    // run editor command
+    pub fn run_command(&mut self, cmd: &str) -> Result<bool> {
+        match cmd {
+            "qa"  | "quitall"  => return Ok(self.quit_editor()),
+            "qa!" | "quitall!" => return Ok(false),
+            "q"   | "quit"     => if self.quit_buffer(true)  { return Ok(false) }
+            "q!"  | "quit!"    => if self.quit_buffer(false) { return Ok(false) }
+            "w"   | "write"    => self.write_file(false)?,
+            "comment"          => self.buffers[self.buffer].toggle_comment(),
+            "recent"           => self.open_recent()?,
+            "files"            => self.open_finder(false)?,
+            "files!"           => self.open_finder(true)?,
+
+            _ => {
+                let goto = cmd
+                    .strip_prefix(':')
+                    .or_else(|| cmd.strip_prefix("goto "));
+
+                if let Some(pos) = goto {
+                    self.buffers[self.buffer].goto(pos);
+                } else if let Some(name) = cmd.strip_prefix("session save ") {
+                    self.save_session(name.trim());
+                } else if let Some(name) = cmd.strip_prefix("session load ") {
+                    self.load_session(name.trim())?;
+                } else if let Some(name) = cmd.strip_prefix("encoding ") {
+                    self.buffers[self.buffer].set_encoding(name.trim());
+                } else if let Some(path) = cmd.strip_prefix("open ") {
+                    self.open_file(String::from(&*tilde(&path)))?;
+                } else {
+                    self.buffers[self.buffer]
+                        .message
+                        .set_message(format!(
+                            "command `{}` not found",
+                            cmd,
+                        ));
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    // add new buffer from file
+    fn open_file(&mut self, path: String) -> Result<()> {
+        match Buffer::new(Some(path.clone())) {
+            Ok(mut buffer) => {
+                Self::restore_position(&mut buffer);
+                buffer.readonly |= self.readonly;
+
+                self.buffers.push(buffer);
+                self.check_swap(self.buffers.len() - 1)?;
+            }
+
+            Err(err) => {
+                self.buffers[self.buffer]
+                    .message
+                    .set_message(Self::open_error(&path, err));
+            }
+        }
+
+        Ok(())
+    }
+
+    // switch to buffer if file is open, otherwise open it
+    fn open_or_switch(&mut self, path: &Path) -> Result<()> {
+        let path = path::absolute(path)?;
+
+        let open = self.buffers
+            .iter()
+            .position(|buffer| {
                 buffer.rows.filepath
-                    .clone().unwrap().display(),
-            ));
+                    .as_ref()
+                    .and_then(|it| path::absolute(it).ok())
+                    .is_some_and(|it| it == path)
+            });
+
+        match open {
+            Some(idx) => self.buffer = idx,
+            None => self.open_file(path.to_string_lossy().into_owned())?,
+        }
+
+        Ok(())
+    }
+
+    // pick recent file to open or switch to
+    fn open_recent(&mut self) -> Result<()> {
+        let files = Recent::load().files();
 
-            buffer.dirty = 0;
-        })?;
+        let items = files
+            .iter()
+            .map(|path| Recent::display(path))
+            .collect::<Vec<String>>();
+
+        let picked = picker::pick(
+            &mut self.buffers[self.buffer],
+            "recent files",
+            &items,
+            |i, lines| finder::preview(&files[i], lines),
+        )?;
+
+        if let Some(i) = picked {
+            self.open_or_switch(&files[i])?;
+        }
 
         Ok(())
     }
 
+    // pick file under current directory to open or switch to
+    fn open_finder(&mut self, refresh: bool) -> Result<()> {
+        let dir = env::current_dir()?;
+        let modified = fs::metadata(&dir).and_then(|it| it.modified()).ok();
+
+        // list files again for new or changed directory,
+        // use `files!` for changes in subdirectories
+        let stale = self.files
+            .as_ref()
+            .is_none_or(|(listed, time, _)| *listed != dir || *time != modified);
+
+        if refresh || stale {
+            self.files = Some((dir.clone(), modified, finder::list_files(&dir)));
+        }
+
+        let files = &self.files.as_ref().unwrap().2;
+
+        let picked = picker::pick(
+            &mut self.buffers[self.buffer],
+            "files",
+            files,
+            |i, lines| finder::preview(&dir.join(&files[i]), lines),
+        )?;
+
+        if let Some(i) = picked {
+            self.open_or_switch(&dir.join(&files[i]))?;
+        }
+
+        Ok(())
+    }
+
+    // run startup commands
+    pub fn run_commands(&mut self, commands: &[String]) -> Result<bool> {
+        for cmd in commands {
+            if !self.run_command(cmd)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
     // process keypresses
-    fn process_keypress(&mut self) -> Result<bool> {
+    fn process_keypress(&mut self, key: KeyEvent) -> Result<bool> {
         let buffer = &mut self.buffers[self.buffer];
 
-        match Reader::read_key()? {
+        match key {
             // quit editor
             KeyEvent {
                 code:      KeyCode::Char('q'),
-                modifiers: KeyModifiers::CONTROL,
+                modifiers: KeyModifiers::CONTROL, ..
             } => return Ok(self.quit_editor()),
 
             // save rows to custom filename
             KeyEvent {
                 code:      KeyCode::Char('w'),
-                modifiers: KeyModifiers::CONTROL,
-            } => {
-                if self.quit_buffer(true) {
-                    return Ok(false);
-                }
-            }
+                modifiers: KeyModifiers::CONTROL, ..
+            } => return Ok(!self.quit_buffer(true)),
 
             // save rows to file
             KeyEvent {
                 code:      KeyCode::Char('s'),
-                modifiers: KeyModifiers::CONTROL,
+                modifiers: KeyModifiers::CONTROL, ..
             } => self.write_file(false)?,
 
             // save rows to custom filename
             KeyEvent {
                 code:      KeyCode::Char('d'),
-                modifiers: KeyModifiers::CONTROL,
+                modifiers: KeyModifiers::CONTROL, ..
             } => self.write_file(true)?,
 
             // cycle through buffers
             KeyEvent {
                 code:      KeyCode::Char('n'),
-                modifiers: KeyModifiers::CONTROL,
+                modifiers: KeyModifiers::CONTROL, ..
             } => {
+                let config = &Config::get_config().autosave;
+
+                // save buffer being left
+                if config.enabled && config.on_switch {
+                    self.autosave(&[self.buffer]);
+                }
+
                 self.buffer =
                     if self.buffer == self.buffers.len() - 1 { 0 }
                     else { self.buffer + 1 };
@@ -167,101 +905,146 @@ impl Editor {
             // add new buffer
             KeyEvent {
                 code:      KeyCode::Char('t'),
-                modifiers: KeyModifiers::CONTROL,
+                modifiers: KeyModifiers::CONTROL, ..
             } => {
-                self.buffers.push(Buffer::new(None));
+                self.buffers.push(Buffer::new(None)?);
                 self.buffer = self.buffers.len() - 1;
             }
 
             KeyEvent {
                 code:      KeyCode::Char('f'),
-                modifiers: KeyModifiers::CONTROL,
+                modifiers: KeyModifiers::CONTROL, ..
             } => self.buffers[self.buffer].find()?,
 
+            // find file to open
+            KeyEvent {
+                code:      KeyCode::Char('p'),
+                modifiers: KeyModifiers::CONTROL, ..
+            } => self.open_finder(false)?,
+
+            // undo edit
+            KeyEvent {
+                code:      KeyCode::Char('z'),
+                modifiers: KeyModifiers::CONTROL, ..
+            } => buffer.undo(),
+
+            // redo edit
+            KeyEvent {
+                code:      KeyCode::Char('y'),
+                modifiers: KeyModifiers::CONTROL, ..
+            } => buffer.redo(),
+
+            // toggle comments
+            KeyEvent {
+                code:      KeyCode::Char('/' | '7'),
+                modifiers: KeyModifiers::CONTROL, ..
+            } => buffer.toggle_comment(),
+
+            // go to position
+            KeyEvent {
+                code:      KeyCode::Char('g'),
+                modifiers: KeyModifiers::CONTROL, ..
+            } => {
+                if let Some(pos) = prompt!(*buffer, "go to") {
+                    buffer.goto(&pos);
+                }
+            }
+
+            // jump to matching bracket
+            KeyEvent {
+                code:      KeyCode::Char('b'),
+                modifiers: KeyModifiers::CONTROL, ..
+            } => buffer.jump_to_match(),
+
             // prompt for input
             KeyEvent {
                 code:      KeyCode::Char('c'),
-                modifiers: KeyModifiers::CONTROL,
+                modifiers: KeyModifiers::CONTROL, ..
             } => {
-                let command = prompt!(&mut self.buffers[self.buffer], "command");
+                let command = prompt!(
+                    &mut self.buffers[self.buffer],
+                    "command",
+                    true,
+                    |&_, _, _| {},
+                    Some(complete::command),
+                    Some(PromptKind::Command),
+                );
 
                 if let Some(cmd) = command {
-                    match cmd.as_str() {
-                        "qa"  | "quitall"  => return Ok(self.quit_editor()),
-                        "qa!" | "quitall!" => return Ok(false),
-                        "q"   | "quit"     => if self.quit_buffer(true)  { return Ok(false) }
-                        "q!"  | "quit!"    => if self.quit_buffer(false) { return Ok(false) }
-                        "w"   | "write"    => self.write_file(false)?,
-
-                        _ => {
-                            if let Some(path) = cmd.strip_prefix("open ") {
-                                // add new buffer from file
-                                self.buffers.push(Buffer::new(Some(
-                                    String::from(&*tilde(&path)),
-                                )));
-
-                                self.buffer = self.buffers.len() - 1;
-                            } else {
-                                self.buffers[self.buffer]
-                                    .message
-                                    .set_message(format!(
-                                        "command `{}` not found",
-                                        cmd,
-                                    ));
-                            }
-                        }
-                    }
+                    return self.run_command(&cmd);
                 }
             }
 
             // move cursor
             KeyEvent {
                 code: dir @ (
-                    KeyCode::Up    |
-                    KeyCode::Down  |
-                    KeyCode::Left  |
-                    KeyCode::Right
+                    KeyCode::Up       |
+                    KeyCode::Down     |
+                    KeyCode::Left     |
+                    KeyCode::Right    |
+                    KeyCode::Home     |
+                    KeyCode::End      |
+                    KeyCode::PageUp   |
+                    KeyCode::PageDown
                 ),
-                modifiers: KeyModifiers::NONE,
+                modifiers: KeyModifiers::NONE, ..
             } => buffer.move_cursor(dir),
 
+            // select with cursor
+            KeyEvent {
+                code: dir @ (
+                    KeyCode::Up       |
+                    KeyCode::Down     |
+                    KeyCode::Left     |
+                    KeyCode::Right    |
+                    KeyCode::Home     |
+                    KeyCode::End      |
+                    KeyCode::PageUp   |
+                    KeyCode::PageDown
+                ),
+                modifiers: KeyModifiers::SHIFT, ..
+            } => buffer.select_cursor(dir),
+
+            // move cursor by word
+            KeyEvent {
+                code: dir @ (KeyCode::Left | KeyCode::Right),
+                modifiers: KeyModifiers::CONTROL, ..
+            } => buffer.move_word(dir == KeyCode::Right),
+
+            // move cursor to document start or end
+            KeyEvent {
+                code: dir @ (KeyCode::Home | KeyCode::End),
+                modifiers: KeyModifiers::CONTROL, ..
+            } => buffer.move_document(dir == KeyCode::End),
+
             // delete char
             KeyEvent {
                 code:      KeyCode::Backspace,
-                modifiers: KeyModifiers::NONE,
+                modifiers: KeyModifiers::NONE, ..
             } => buffer.delete_char(),
 
             // insert newline
             KeyEvent {
                 code:      KeyCode::Enter,
-                modifiers: KeyModifiers::NONE,
+                modifiers: KeyModifiers::NONE, ..
             } => buffer.insert_newline(),
 
-            // insert char or tab
+            // insert char
             KeyEvent {
-                code:      key @ (KeyCode::Char(..) | KeyCode::Tab),
-                modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
-            } => {
-                match key {
-                    KeyCode::Tab => {
-                        let config = Config::get_config();
-
-                        let soft_tabs    = config.tabs.soft;
-                        let indent_width = config.indent.width;
-
-                        if !soft_tabs {
-                            buffer.insert_char('\t');
-                        } else {
-                            // insert spaces instead of tabs
-                            for _ in 0..indent_width {
-                                buffer.insert_char(' ');
-                            }
-                        }
-                    }
-                    KeyCode::Char(chr) => buffer.insert_char(chr),
-                    _ => {}
-                }
-            }
+                code:      KeyCode::Char(chr),
+                modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT, ..
+            } => buffer.type_char(chr),
+
+            // indent rows
+            KeyEvent {
+                code:      KeyCode::Tab,
+                modifiers: KeyModifiers::NONE, ..
+            } => buffer.indent_rows(),
+
+            // dedent rows
+            KeyEvent {
+                code: KeyCode::BackTab, ..
+            } => buffer.dedent_rows(),
 
             _ => {}
         }
@@ -271,6 +1054,9 @@ impl Editor {
 
     // run editor
     pub fn run(&mut self) -> Result<bool> {
+        self.auto_reload();
+        self.update_swaps();
+
         self.buffers[self.buffer].current_buf = self.buffer;
 
         // send buffers to buffer for tabline
@@ -283,6 +1069,43 @@ impl Editor {
             .collect::<Vec<_>>();
 
         self.buffers[self.buffer].refresh_screen()?;
-        self.process_keypress()
+
+        // wait for input, handling timer events
+        loop {
+            match Reader::read_input()? {
+                Input::Key(key) => {
+                    self.last_input = Instant::now();
+                    return self.process_keypress(key);
+                }
+
+                Input::Paste(text) => {
+                    self.last_input = Instant::now();
+                    self.buffers[self.buffer].paste(&text);
+
+                    return Ok(true);
+                }
+
+                Input::FocusLost => {
+                    let config = &Config::get_config().autosave;
+
+                    if config.enabled && config.on_focus_lost {
+                        let all = (0..self.buffers.len()).collect::<Vec<_>>();
+
+                        if self.autosave(&all) {
+                            return Ok(true);
+                        }
+                    }
+                }
+
+                Input::Tick => {
+                    self.update_swaps();
+
+                    // redraw if buffers changed
+                    if self.autosave_idle() | self.auto_reload() {
+                        return Ok(true);
+                    }
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,253 @@
+use std::char::REPLACEMENT_CHARACTER;
+use std::io::{Error, Result};
+
+// byte order marks
+const UTF8_BOM: &[u8]     = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+// bytes checked when detecting
+const SAMPLE_LEN: usize = 8192;
+
+// file encoding
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Encoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+impl Encoding {
+    // all encodings
+    const ALL: [Self; 5] = [
+        Self::Utf8,
+        Self::Utf8Bom,
+        Self::Utf16Le,
+        Self::Utf16Be,
+        Self::Latin1,
+    ];
+
+    // detect encoding of bytes
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(UTF8_BOM) {
+            return Self::Utf8Bom;
+        } else if bytes.starts_with(UTF16_LE_BOM) {
+            return Self::Utf16Le;
+        } else if bytes.starts_with(UTF16_BE_BOM) {
+            return Self::Utf16Be;
+        }
+
+        let sample = &bytes[..bytes.len().min(SAMPLE_LEN)];
+
+        // count nul bytes at even and odd positions
+        let (even, odd) = sample
+            .iter()
+            .enumerate()
+            .filter(|(_, byte)| **byte == 0)
+            .fold((0, 0), |(even, odd), (i, _)| {
+                if i % 2 == 0 { (even + 1, odd) }
+                else { (even, odd + 1) }
+            });
+
+        // utf-16 text without bom has nul high bytes
+        let half = sample.len() / 2;
+
+        if sample.len().is_multiple_of(2) && half > 0 {
+            if odd * 10 > half * 4 && even * 10 < half {
+                return Self::Utf16Le;
+            } else if even * 10 > half * 4 && odd * 10 < half {
+                return Self::Utf16Be;
+            }
+        }
+
+        if std::str::from_utf8(bytes).is_ok() {
+            Self::Utf8
+        } else {
+            Self::Latin1
+        }
+    }
+
+    // check if bytes are likely binary
+    pub fn is_binary(self, bytes: &[u8]) -> bool {
+        match self {
+            Self::Utf16Le | Self::Utf16Be => false,
+
+            _ => {
+                bytes[..bytes.len().min(SAMPLE_LEN)]
+                    .contains(&0)
+            }
+        }
+    }
+
+    // decode bytes to string
+    pub fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            Self::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+
+            Self::Utf8Bom => {
+                let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+                String::from_utf8_lossy(bytes).into_owned()
+            }
+
+            Self::Utf16Le | Self::Utf16Be => {
+                let bom =
+                    if self == Self::Utf16Le { UTF16_LE_BOM }
+                    else { UTF16_BE_BOM };
+
+                let bytes = bytes.strip_prefix(bom).unwrap_or(bytes);
+
+                // join byte pairs into code units
+                let units = bytes
+                    .chunks_exact(2)
+                    .map(|pair| {
+                        if self == Self::Utf16Le {
+                            u16::from_le_bytes([pair[0], pair[1]])
+                        } else {
+                            u16::from_be_bytes([pair[0], pair[1]])
+                        }
+                    });
+
+                char::decode_utf16(units)
+                    .map(|chr| chr.unwrap_or(REPLACEMENT_CHARACTER))
+                    .collect()
+            }
+
+            Self::Latin1 => bytes.iter().map(|&byte| byte as char).collect(),
+        }
+    }
+
+    // encode string to bytes
+    pub fn encode(self, contents: &str) -> Result<Vec<u8>> {
+        match self {
+            Self::Utf8 => Ok(contents.as_bytes().to_vec()),
+
+            Self::Utf8Bom => Ok([UTF8_BOM, contents.as_bytes()].concat()),
+
+            Self::Utf16Le | Self::Utf16Be => {
+                let bom =
+                    if self == Self::Utf16Le { UTF16_LE_BOM }
+                    else { UTF16_BE_BOM };
+
+                let mut bytes = bom.to_vec();
+
+                for unit in contents.encode_utf16() {
+                    if self == Self::Utf16Le {
+                        bytes.extend(unit.to_le_bytes());
+                    } else {
+                        bytes.extend(unit.to_be_bytes());
+                    }
+                }
+
+                Ok(bytes)
+            }
+
+            Self::Latin1 => {
+                contents
+                    .chars()
+                    .map(|chr| {
+                        u8::try_from(chr).map_err(|_| {
+                            Error::other(format!("`{}` cannot be encoded as latin-1", chr))
+                        })
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    // name of encoding
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Utf8    => "utf-8",
+            Self::Utf8Bom => "utf-8-bom",
+            Self::Utf16Le => "utf-16le",
+            Self::Utf16Be => "utf-16be",
+            Self::Latin1  => "latin-1",
+        }
+    }
+
+    // get encoding from name
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase().replace('_', "-");
+
+        match name.as_str() {
+            "utf8"   => Some(Self::Utf8),
+            "latin1" | "iso-8859-1" => Some(Self::Latin1),
+
+            _ => Self::ALL.into_iter().find(|it| it.name() == name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // text with ascii, latin-1 and astral chars
+    const TEXT: &str = "fn main() {}\ncafé ☕ 𝄞\n";
+
+    #[test]
+    fn detects_boms() {
+        assert_eq!(Encoding::detect(b"\xEF\xBB\xBFabc"), Encoding::Utf8Bom);
+        assert_eq!(Encoding::detect(b"\xFF\xFEa\0"),     Encoding::Utf16Le);
+        assert_eq!(Encoding::detect(b"\xFE\xFF\0a"),     Encoding::Utf16Be);
+    }
+
+    #[test]
+    fn detects_utf16_without_bom() {
+        assert_eq!(Encoding::detect(b"a\0b\0c\0\n\0"), Encoding::Utf16Le);
+        assert_eq!(Encoding::detect(b"\0a\0b\0c\0\n"), Encoding::Utf16Be);
+    }
+
+    #[test]
+    fn detects_utf8_and_latin1() {
+        assert_eq!(Encoding::detect(b""),                 Encoding::Utf8);
+        assert_eq!(Encoding::detect(TEXT.as_bytes()),     Encoding::Utf8);
+        assert_eq!(Encoding::detect(b"caf\xE9\n"),        Encoding::Latin1);
+    }
+
+    #[test]
+    fn detects_binary() {
+        let bytes = b"\x7FELF\x02\x01\x01\0\0\0\0\0\0\0\0\0\x03\0";
+        let encoding = Encoding::detect(bytes);
+
+        assert!(encoding.is_binary(bytes));
+        assert!(!Encoding::Utf8.is_binary(TEXT.as_bytes()));
+    }
+
+    #[test]
+    fn round_trips_unicode_encodings() {
+        for encoding in [Encoding::Utf8, Encoding::Utf8Bom, Encoding::Utf16Le, Encoding::Utf16Be] {
+            let bytes = encoding.encode(TEXT).unwrap();
+
+            assert_eq!(Encoding::detect(&bytes), encoding, "{}", encoding.name());
+            assert_eq!(encoding.decode(&bytes), TEXT, "{}", encoding.name());
+        }
+    }
+
+    #[test]
+    fn round_trips_latin1() {
+        let bytes = b"caf\xE9 \xA9 na\xEFve\n";
+        let text  = Encoding::Latin1.decode(bytes);
+
+        assert_eq!(text, "café © naïve\n");
+        assert_eq!(Encoding::Latin1.encode(&text).unwrap(), bytes);
+    }
+
+    #[test]
+    fn rejects_chars_outside_latin1() {
+        assert!(Encoding::Latin1.encode("☕").is_err());
+    }
+
+    #[test]
+    fn looks_up_names() {
+        for encoding in Encoding::ALL {
+            assert_eq!(Encoding::from_name(encoding.name()), Some(encoding));
+        }
+
+        assert_eq!(Encoding::from_name("UTF_16LE"),   Some(Encoding::Utf16Le));
+        assert_eq!(Encoding::from_name("iso-8859-1"), Some(Encoding::Latin1));
+        assert_eq!(Encoding::from_name("ebcdic"),     None);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::encoding::Encoding;
+use crate::rows::CONTROL_CHR;
+
+use ignore::WalkBuilder;
+
+use std::mem;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+// maximum files listed
+const MAX_FILES: usize = 50_000;
+
+// bytes read for preview
+const PREVIEW_LEN: u64 = 16_384;
+
+// list files under directory, respecting ignore files
+pub fn list_files(dir: &Path) -> Vec<String> {
+    WalkBuilder::new(dir)
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|kind| kind.is_file()))
+        .filter_map(|entry| {
+            let path = entry.path().strip_prefix(dir).ok()?;
+            Some(path.to_string_lossy().into_owned())
+        })
+        .take(MAX_FILES)
+        .collect()
+}
+
+// score candidate for query, none if query does not match
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let query = query.chars().collect::<Vec<char>>();
+
+    // start of file name
+    let name_start = candidate
+        .rfind('/')
+        .map_or(0, |i| candidate[..i].chars().count() + 1);
+
+    // best score matching query prefix ending before current char
+    let mut best: Vec<Option<i64>> = vec![None; query.len()];
+
+    // score matching query prefix ending at previous char
+    let mut last: Vec<Option<i64>> = vec![None; query.len()];
+
+    // scores ending at current char
+    let mut current = vec![None; query.len()];
+
+    let mut prev_char = None;
+    let mut len = 0;
+
+    for (idx, c) in candidate.chars().enumerate() {
+        current.fill(None);
+
+        for (i, &q) in query.iter().enumerate() {
+            if !chars_match(c, q) {
+                continue;
+            }
+
+            // score of rest of query before this char
+            let before = if i == 0 {
+                Some(0)
+            } else {
+                // consecutive characters
+                let consecutive = last[i - 1].map(|score| score + 5);
+                best[i - 1].max(consecutive)
+            };
+
+            let mut score = match before {
+                Some(score) => score + 1,
+                None => continue,
+            };
+
+            // start of word
+            if prev_char.is_none_or(|prev| matches!(prev, '/' | '_' | '-' | '.' | ' ')) {
+                score += 8;
+            }
+
+            // matches in file name
+            if idx >= name_start {
+                score += 2;
+            }
+
+            if c == q {
+                score += 1;
+            }
+
+            current[i] = Some(score);
+        }
+
+        for (best, &score) in best.iter_mut().zip(&current) {
+            *best = (*best).max(score);
+        }
+
+        mem::swap(&mut last, &mut current);
+        prev_char = Some(c);
+        len      += 1;
+    }
+
+    best[query.len() - 1].map(|score| score - len / 8)
+}
+
+// compare characters ignoring case
+fn chars_match(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+// get first lines of file for preview
+pub fn preview(path: &Path, lines: usize) -> Vec<String> {
+    let mut bytes = Vec::new();
+
+    let read = File::open(path)
+        .and_then(|file| file.take(PREVIEW_LEN).read_to_end(&mut bytes));
+
+    if let Err(err) = read {
+        return vec![format!("[error] {}", err)];
+    }
+
+    // drop character cut off at end of preview
+    if let Err(err) = std::str::from_utf8(&bytes) {
+        if err.error_len().is_none() {
+            bytes.truncate(err.valid_up_to());
+        }
+    }
+
+    let encoding = Encoding::detect(&bytes);
+
+    if encoding.is_binary(&bytes) {
+        return vec![String::from("[binary file]")];
+    }
+
+    encoding
+        .decode(&bytes)
+        .lines()
+        .take(lines)
+        .map(|line| {
+            // keep control characters away from terminal
+            line.replace('\t', "    ")
+                .chars()
+                .map(|c| if c.is_control() { CONTROL_CHR } else { c })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::testing::temp_file;
+
+    use std::fs;
+
+    #[test]
+    fn scores_matching_candidates() {
+        assert_eq!(fuzzy_score("xyz", "src/editor.rs"), None);
+        assert_eq!(fuzzy_score("rse", "src/editor.rs"), None);
+        assert!(fuzzy_score("EDIT", "src/editor.rs").is_some());
+    }
+
+    #[test]
+    fn prefers_file_name_and_consecutive_matches() {
+        let name      = fuzzy_score("main", "src/main.rs").unwrap();
+        let dir       = fuzzy_score("main", "main/lib.rs").unwrap();
+        let scattered = fuzzy_score("main", "src/my_animation.rs").unwrap();
+
+        assert!(name > dir);
+        assert!(name > scattered);
+    }
+
+    #[test]
+    fn keeps_utf8_when_preview_cuts_char() {
+        // multibyte char split by preview length
+        let mut contents = "a".repeat(PREVIEW_LEN as usize - 1);
+        contents.push_str("é\nrest");
+
+        let path = temp_file("preview.txt", contents.as_bytes());
+
+        let lines = preview(&path, 1);
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(lines, vec!["a".repeat(PREVIEW_LEN as usize - 1)]);
+    }
+
+    #[test]
+    fn replaces_control_chars_in_preview() {
+        let path = temp_file("control.txt", b"a\tb\x1b[2J\rc\n");
+
+        let lines = preview(&path, 1);
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(lines, ["a    b\u{FFFD}[2J\u{FFFD}c"]);
+    }
+}
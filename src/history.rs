@@ -0,0 +1,352 @@
+use crate::state;
+
+use serde::{Deserialize, Serialize};
+
+use std::fs;
+use std::ops::Range;
+use std::io::{Error, Result};
+use std::path::{Path, PathBuf};
+
+// maximum undo steps
+const MAX_STEPS: usize = 500;
+
+// maximum bytes of rows kept in older undo steps
+const MAX_CHANGE_BYTES: usize = 32 * 1024 * 1024;
+
+// maximum undo steps saved to disk
+const MAX_SAVED_STEPS: usize = 100;
+
+// maximum bytes of rows saved to disk per stack
+const MAX_SAVED_BYTES: usize = 4 * 1024 * 1024;
+
+// kind of edit for grouping
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum EditKind {
+    Insert,
+    Delete,
+    Other,
+}
+
+// rows replaced by edit, with contents to restore them to
+#[derive(Clone, Serialize, Deserialize)]
+struct Change {
+    // rows kept from start
+    prefix: usize,
+
+    // rows kept from end
+    suffix: usize,
+
+    // rows replacing middle
+    rows: Vec<String>,
+
+    // cursor position
+    cursor: (usize, usize),
+}
+
+impl Change {
+    // get change restoring range of rows
+    fn new(range: Range<usize>, len: usize, rows: Vec<String>, cursor: (usize, usize)) -> Self {
+        Self {
+            prefix: range.start,
+            suffix: len - range.end,
+            rows,
+            cursor,
+        }
+    }
+
+    // extend change to cover range, taking newly covered rows as they are
+    fn extend(&mut self, range: Range<usize>, len: usize, rows: impl Fn(Range<usize>) -> Vec<String>) {
+        let end = len - self.suffix;
+
+        let start   = range.start.min(self.prefix);
+        let new_end = range.end.max(end);
+
+        let mut extended = rows(start..self.prefix);
+        extended.append(&mut self.rows);
+        extended.extend(rows(end..new_end));
+
+        self.rows   = extended;
+        self.prefix = start;
+        self.suffix = len - new_end;
+    }
+
+    // bytes of rows kept
+    fn len(&self) -> usize {
+        self.rows.iter().map(String::len).sum()
+    }
+}
+
+// changes to apply in order, most recent last
+#[derive(Default, Serialize, Deserialize)]
+struct Stack {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    changes: Vec<Change>,
+}
+
+impl Stack {
+    // push change, dropping oldest ones over limits
+    fn push(&mut self, change: Change) {
+        self.changes.push(change);
+
+        let mut bytes = self.changes.iter().map(Change::len).sum::<usize>();
+
+        while self.changes.len() > MAX_STEPS || bytes > MAX_CHANGE_BYTES {
+            bytes -= self.changes.remove(0).len();
+        }
+    }
+
+    // remove all changes
+    fn clear(&mut self) {
+        self.changes.clear();
+    }
+
+    // copy keeping most recent steps within byte limit
+    fn recent(&self, steps: usize, max_bytes: usize) -> Self {
+        let mut bytes = 0;
+
+        let kept = self.changes
+            .iter()
+            .rev()
+            .take(steps)
+            .take_while(|change| {
+                bytes += change.len();
+                bytes <= max_bytes
+            })
+            .count();
+
+        Self {
+            changes: self.changes[self.changes.len() - kept..].to_vec(),
+        }
+    }
+
+    // apply most recent change, pushing its inverse onto other stack
+    fn apply(
+        &mut self,
+        other: &mut Stack,
+        len: usize,
+        cursor: (usize, usize),
+        replace: impl FnOnce(Range<usize>, Vec<String>) -> Vec<String>,
+    ) -> Option<(usize, usize)> {
+        let change = self.changes.pop()?;
+        let range  = change.prefix..len - change.suffix;
+
+        // replaced rows undo this change, keeping same prefix and suffix
+        other.push(Change {
+            prefix: change.prefix,
+            suffix: change.suffix,
+            rows:   replace(range, change.rows),
+            cursor,
+        });
+
+        Some(change.cursor)
+    }
+}
+
+pub struct History {
+    // changes undoing edits
+    undo: Stack,
+
+    // changes redoing undos
+    redo: Stack,
+
+    // kind of last edit
+    last: Option<EditKind>,
+}
+
+// history saved for file contents
+#[derive(Serialize, Deserialize)]
+struct SavedHistory {
+    // length and hash of file contents
+    hash: String,
+
+    // changes undoing edits
+    #[serde(default)]
+    undo: Stack,
+
+    // changes redoing undos
+    #[serde(default)]
+    redo: Stack,
+}
+
+impl History {
+    // create new history
+    pub fn new() -> Self {
+        Self {
+            undo: Stack::default(),
+            redo: Stack::default(),
+            last: None,
+        }
+    }
+
+    // check if there is nothing to undo or redo
+    pub fn is_empty(&self) -> bool {
+        self.undo.changes.is_empty() && self.redo.changes.is_empty()
+    }
+
+    // record rows in range of len rows before edit changes them
+    pub fn record(
+        &mut self,
+        kind: EditKind,
+        range: Range<usize>,
+        len: usize,
+        cursor: (usize, usize),
+        rows: impl Fn(Range<usize>) -> Vec<String>,
+    ) {
+        let range = range.start.min(len)..range.end.min(len);
+
+        // group repeated inserts and deletes
+        if kind != EditKind::Other && self.last == Some(kind) {
+            if let Some(change) = self.undo.changes.last_mut() {
+                return change.extend(range, len, rows);
+            }
+        }
+
+        self.undo.push(Change::new(range.clone(), len, rows(range), cursor));
+        self.redo.clear();
+        self.last = Some(kind);
+    }
+
+    // end current edit group
+    pub fn break_group(&mut self) {
+        self.last = None;
+    }
+
+    // undo last edit to len rows, returning cursor to restore
+    pub fn undo(
+        &mut self,
+        len: usize,
+        cursor: (usize, usize),
+        replace: impl FnOnce(Range<usize>, Vec<String>) -> Vec<String>,
+    ) -> Option<(usize, usize)> {
+        self.last = None;
+        self.undo.apply(&mut self.redo, len, cursor, replace)
+    }
+
+    // redo last undo to len rows, returning cursor to restore
+    pub fn redo(
+        &mut self,
+        len: usize,
+        cursor: (usize, usize),
+        replace: impl FnOnce(Range<usize>, Vec<String>) -> Vec<String>,
+    ) -> Option<(usize, usize)> {
+        self.last = None;
+        self.redo.apply(&mut self.undo, len, cursor, replace)
+    }
+
+    // get state file for history of file
+    fn saved_path(path: &Path) -> Result<PathBuf> {
+        let dir = state::state_dir("undo")?;
+        Ok(dir.join(format!("{}.toml", state::escape_path(path))))
+    }
+
+    // load history saved for file with same contents
+    pub fn load(path: &Path, key: &str) -> Option<Self> {
+        let saved_path = Self::saved_path(path).ok()?;
+
+        let saved: SavedHistory = fs::read_to_string(&saved_path)
+            .ok()
+            .and_then(|contents| toml::from_str(&contents).ok())?;
+
+        // file changed since history was saved
+        if saved.hash != key {
+            let _ = fs::remove_file(saved_path);
+            return None;
+        }
+
+        Some(Self {
+            undo: saved.undo,
+            redo: saved.redo,
+            last: None,
+        })
+    }
+
+    // save history for file contents
+    pub fn save(&self, path: &Path, key: &str) -> Result<()> {
+        // keep most recent steps
+        let saved = SavedHistory {
+            hash: key.to_string(),
+            undo: self.undo.recent(MAX_SAVED_STEPS, MAX_SAVED_BYTES),
+            redo: self.redo.recent(MAX_SAVED_STEPS, MAX_SAVED_BYTES),
+        };
+
+        let contents = toml::to_string(&saved).map_err(Error::other)?;
+        state::write_private(&Self::saved_path(path)?, contents.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // record edit of rows in range, then replace them
+    fn edit(history: &mut History, doc: &mut Vec<String>, kind: EditKind, range: Range<usize>, new: &[&str]) {
+        history.record(kind, range.clone(), doc.len(), (0, range.start), |range| doc[range].to_vec());
+        doc.splice(range, new.iter().map(|it| it.to_string()));
+    }
+
+    // replace rows of doc, returning replaced rows
+    fn replace(doc: &mut Vec<String>) -> impl FnOnce(Range<usize>, Vec<String>) -> Vec<String> + '_ {
+        |range, rows| doc.splice(range, rows).collect()
+    }
+
+    #[test]
+    fn undoes_and_redoes_row_changes() {
+        let mut history = History::new();
+        let mut doc = vec![String::from("a"), String::from("b")];
+
+        edit(&mut history, &mut doc, EditKind::Other, 1..2, &["b1", "b2"]);
+        edit(&mut history, &mut doc, EditKind::Other, 0..1, &[]);
+
+        assert_eq!(doc, ["b1", "b2"]);
+
+        history.undo(doc.len(), (0, 0), replace(&mut doc));
+        assert_eq!(doc, ["a", "b1", "b2"]);
+
+        history.undo(doc.len(), (0, 0), replace(&mut doc));
+        assert_eq!(doc, ["a", "b"]);
+        assert!(history.undo(doc.len(), (0, 0), replace(&mut doc)).is_none());
+
+        history.redo(doc.len(), (0, 0), replace(&mut doc));
+        history.redo(doc.len(), (0, 0), replace(&mut doc));
+        assert_eq!(doc, ["b1", "b2"]);
+    }
+
+    #[test]
+    fn groups_edits_across_rows() {
+        let mut history = History::new();
+        let mut doc = vec![String::from("a"), String::from("b"), String::from("c")];
+
+        // join rows like repeated backspace
+        edit(&mut history, &mut doc, EditKind::Delete, 1..3, &["bc"]);
+        edit(&mut history, &mut doc, EditKind::Delete, 0..2, &["abc"]);
+
+        let cursor = history.undo(doc.len(), (0, 0), replace(&mut doc));
+
+        assert_eq!(doc, ["a", "b", "c"]);
+        assert_eq!(cursor, Some((0, 1)));
+    }
+
+    #[test]
+    fn saves_recent_steps_within_bytes() {
+        let mut history = History::new();
+        let mut doc = vec![String::new()];
+
+        for row in ["aaaa", "bbbb", "cccc", "dddd"] {
+            edit(&mut history, &mut doc, EditKind::Other, 0..1, &[row]);
+        }
+
+        assert_eq!(history.undo.recent(10, 11).changes.len(), 2);
+        assert_eq!(history.undo.recent(2, 100).changes.len(), 2);
+        assert!(history.undo.recent(10, 3).changes.is_empty());
+
+        // saved changes still apply to contents
+        let mut saved = History {
+            undo: history.undo.recent(2, 100),
+            redo: Stack::default(),
+            last: None,
+        };
+
+        while saved.undo(doc.len(), (0, 0), replace(&mut doc)).is_some() {}
+        assert_eq!(doc, ["bbbb"]);
+    }
+}
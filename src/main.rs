@@ -1,23 +1,52 @@
+mod args;
 mod buffer;
+mod complete;
 mod config;
 mod contents;
 mod cursor;
 mod editor;
+mod encoding;
+mod finder;
+mod history;
 mod message;
+mod picker;
+mod positions;
+mod prompts;
+mod recent;
 mod reader;
 mod rows;
 mod search;
+mod session;
+mod state;
+mod swap;
 mod syntax;
 mod utils;
 
+#[cfg(feature = "tree-sitter")]
+mod treesitter;
+
+#[cfg(test)]
+mod testing;
+
+use crate::args::Args;
 use crate::config::Config;
 use crate::editor::Editor;
 
-use crossterm::{terminal, execute, Result};
-use crossterm::cursor::{MoveTo, SetCursorShape};
+use crossterm::{terminal, execute};
+use crossterm::cursor::MoveTo;
 use crossterm::terminal::ClearType;
+use crossterm::event::{
+    EnableFocusChange, DisableFocusChange,
+    EnableBracketedPaste, DisableBracketedPaste,
+};
+
+use std::io::{stdout, Result};
+use std::sync::Mutex;
+use std::process::exit;
+use std::panic::{self, AssertUnwindSafe};
 
-use std::io::stdout;
+// panic message shown after terminal is restored
+static PANIC_MESSAGE: Mutex<Option<String>> = Mutex::new(None);
 
 // clear terminal screen
 fn clear_screen() -> Result<()> {
@@ -30,7 +59,9 @@ fn clear_screen() -> Result<()> {
         stdout(),
         terminal::Clear(ClearType::All),
         MoveTo(0, 0),
-        SetCursorShape(cursor_shape),
+        cursor_shape,
+        DisableFocusChange,
+        DisableBracketedPaste,
     )?;
 
     Ok(())
@@ -41,15 +72,26 @@ struct CleanUp;
 impl Drop for CleanUp {
     // disable raw mode and clear on exit
     fn drop(&mut self) {
-        terminal::disable_raw_mode().unwrap();
-        clear_screen().unwrap();
+        let _ = terminal::disable_raw_mode();
+        let _ = clear_screen();
     }
 }
 
 fn main() -> Result<()> {
-    let _clean = CleanUp;
+    // parse arguments before entering editor
+    let args = match Args::parse() {
+        Ok(Some(args)) => args,
+        Ok(None) => return Ok(()),
+
+        Err(err) => {
+            eprintln!("fe: {}", err);
+            exit(1);
+        }
+    };
+
+    let clean = CleanUp;
 
-    // set cursor shape
+    // set cursor shape and report focus changes and pastes
     let cursor_shape = Config::get_config()
         .cursor
         .shape
@@ -57,15 +99,70 @@ fn main() -> Result<()> {
 
     execute!(
         stdout(),
-        SetCursorShape(cursor_shape)
+        cursor_shape,
+        EnableFocusChange,
+        EnableBracketedPaste,
     )?;
 
     // enter raw mode
     terminal::enable_raw_mode()?;
 
+    // keep panic message instead of printing in raw mode
+    panic::set_hook(Box::new(|info| {
+        let mut message = PANIC_MESSAGE
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+
+        *message = Some(info.to_string());
+    }));
+
+    let mut editor = None;
+
     // run editor
-    let mut editor = Editor::new();
-    while editor.run()? {}
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let editor = editor.insert(Editor::new(&args)?);
 
-    Ok(())
+        if editor.run_commands(&args.commands)? {
+            while editor.run()? {}
+        }
+
+        editor.close();
+
+        Ok(())
+    }));
+
+    match result {
+        Ok(result) => result,
+
+        Err(_) => {
+            // restore terminal before printing
+            drop(clean);
+
+            let message = PANIC_MESSAGE
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .take()
+                .unwrap_or_else(|| String::from("unknown panic"));
+
+            eprintln!("fe: {}", message);
+
+            // save unsaved changes
+            if let Some(editor) = &editor {
+                let saved = editor.write_recovery();
+
+                if !saved.is_empty() {
+                    eprintln!("fe: unsaved buffers written to:");
+                }
+
+                for path in saved {
+                    match path {
+                        Ok(path) => eprintln!("  {}", path.display()),
+                        Err(err) => eprintln!("  [error] {}", err),
+                    }
+                }
+            }
+
+            exit(101);
+        }
+    }
 }
@@ -0,0 +1,174 @@
+use crate::buffer::Buffer;
+use crate::finder;
+use crate::reader::Reader;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use std::io::Result;
+
+// list drawn over rows
+pub struct Overlay {
+    // title above items
+    pub title: String,
+
+    // typed filter
+    pub query: String,
+
+    // items matching query
+    pub items: Vec<String>,
+
+    // selected item
+    pub selected: usize,
+
+    // lines shown below items
+    pub preview: Vec<String>,
+}
+
+impl Overlay {
+    // create overlay with first item selected
+    pub fn new(title: &str, items: Vec<String>) -> Self {
+        Self {
+            title: String::from(title),
+            query: String::new(),
+            items,
+            selected: 0,
+            preview:  Vec::new(),
+        }
+    }
+
+    // height of item list
+    fn list_height(&self, height: usize) -> usize {
+        if self.preview.is_empty() { height.saturating_sub(1) }
+        else { height.saturating_sub(2) / 2 }
+    }
+
+    // first item shown for height
+    fn offset(&self, height: usize) -> usize {
+        let list_height = self.list_height(height);
+        self.selected.saturating_sub(list_height.saturating_sub(1))
+    }
+
+    // get lines to draw, with whether each is highlighted
+    pub fn lines(&self, height: usize) -> Vec<(String, bool)> {
+        let list_height = self.list_height(height);
+
+        let mut lines = vec![(format!("-- {} -- {}", self.title, self.query), false)];
+
+        if self.items.is_empty() {
+            lines.push((String::from("  no matches"), false));
+        }
+
+        let items = self.items
+            .iter()
+            .enumerate()
+            .skip(self.offset(height))
+            .take(list_height)
+            .map(|(i, item)| (format!("  {}", item), i == self.selected));
+
+        lines.extend(items);
+
+        // show preview below list
+        if !self.preview.is_empty() {
+            lines.resize(list_height + 1, (String::new(), false));
+            lines.push((String::from("-- preview --"), false));
+
+            lines.extend(
+                self.preview
+                    .iter()
+                    .map(|line| (format!("  {}", line), false)),
+            );
+        }
+
+        lines
+    }
+
+    // get line of selected item
+    pub fn selected_line(&self, height: usize) -> usize {
+        self.selected - self.offset(height) + 1
+    }
+
+    // move selection by amount
+    fn move_selection(&mut self, amount: isize) {
+        let last = self.items.len().saturating_sub(1) as isize;
+        self.selected = (self.selected as isize + amount).clamp(0, last) as usize;
+    }
+}
+
+// pick item from list with fuzzy filter, returning its index
+pub fn pick(
+    buffer: &mut Buffer,
+    title: &str,
+    items: &[String],
+    preview: impl Fn(usize, usize) -> Vec<String>,
+) -> Result<Option<usize>> {
+    let height = buffer.term_size.1 - 1;
+    let page   = height as isize / 2;
+
+    // indices of items matching query
+    let mut matches = (0..items.len()).collect::<Vec<usize>>();
+
+    buffer.overlay = Some(Overlay::new(title, items.to_vec()));
+
+    let picked = loop {
+        let overlay = buffer.overlay.as_mut().unwrap();
+
+        overlay.preview = match matches.get(overlay.selected) {
+            Some(&idx) => preview(idx, height.saturating_sub(2) / 2),
+            None => Vec::new(),
+        };
+
+        buffer.refresh_screen()?;
+
+        let key = Reader::read_key()?;
+        let overlay = buffer.overlay.as_mut().unwrap();
+
+        let mut filter = false;
+
+        match (key.code, key.modifiers) {
+            (KeyCode::Esc, _) => break None,
+
+            (KeyCode::Enter, _) if !matches.is_empty() => break Some(matches[overlay.selected]),
+
+            (KeyCode::Up, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
+                overlay.move_selection(-1)
+            }
+
+            (KeyCode::Down, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => {
+                overlay.move_selection(1)
+            }
+
+            (KeyCode::PageUp,   _) => overlay.move_selection(-page),
+            (KeyCode::PageDown, _) => overlay.move_selection(page),
+
+            (KeyCode::Backspace, _) => filter = overlay.query.pop().is_some(),
+
+            (KeyCode::Char(chr), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                overlay.query.push(chr);
+                filter = true;
+            }
+
+            _ => {}
+        }
+
+        if filter {
+            let mut scored = items
+                .iter()
+                .enumerate()
+                .filter_map(|(i, item)| {
+                    finder::fuzzy_score(&overlay.query, item).map(|score| (score, i))
+                })
+                .collect::<Vec<_>>();
+
+            // best score first, keeping list order for ties
+            scored.sort_by_key(|&(score, i)| (-score, i));
+
+            matches = scored.into_iter().map(|(_, i)| i).collect();
+
+            overlay.items    = matches.iter().map(|&i| items[i].clone()).collect();
+            overlay.selected = 0;
+        }
+    };
+
+    buffer.overlay = None;
+    Ok(picked)
+}
@@ -0,0 +1,61 @@
+use crate::state;
+
+use serde::{Deserialize, Serialize};
+
+use std::io::Result;
+use std::path::{self, Path, PathBuf};
+
+// maximum files remembered
+const MAX_FILES: usize = 500;
+
+// cursor positions of recent files
+#[derive(Serialize, Deserialize, Default)]
+pub struct Positions {
+    // positions, most recent first
+    #[serde(default)]
+    files: Vec<FilePosition>,
+}
+
+// cursor position in file
+#[derive(Serialize, Deserialize)]
+pub struct FilePosition {
+    // absolute file path
+    pub path: PathBuf,
+
+    // cursor position
+    pub cursor: (usize, usize),
+
+    // row and column offsets
+    pub offset: (usize, usize),
+}
+
+impl Positions {
+    // load positions, using none if missing
+    pub fn load() -> Self {
+        state::load_toml("positions.toml")
+    }
+
+    // save positions
+    pub fn save(&self) -> Result<()> {
+        state::save_toml("positions.toml", self)
+    }
+
+    // get position for file
+    pub fn get(&self, path: &Path) -> Option<&FilePosition> {
+        let path = path::absolute(path).ok()?;
+        self.files.iter().find(|it| it.path == path)
+    }
+
+    // remember position as most recent
+    pub fn remember(&mut self, mut position: FilePosition) {
+        if let Ok(path) = path::absolute(&position.path) {
+            position.path = path;
+        }
+
+        self.files.retain(|it| it.path != position.path);
+        self.files.insert(0, position);
+
+        // evict least recently used
+        self.files.truncate(MAX_FILES);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::state;
+
+use serde::{Deserialize, Serialize};
+
+use std::io::Result;
+
+// maximum entries remembered per prompt
+const MAX_ENTRIES: usize = 200;
+
+// prompts with separate history
+#[derive(Clone, Copy)]
+pub enum PromptKind {
+    Command,
+    Search,
+}
+
+impl PromptKind {
+    // whether arrows keep recalling entries after one is recalled
+    pub fn cycles_history(self) -> bool {
+        // recalled search runs at once, arrows then move between matches
+        !matches!(self, Self::Search)
+    }
+}
+
+// submitted prompt inputs
+#[derive(Serialize, Deserialize, Default)]
+pub struct PromptHistory {
+    // commands, most recent first
+    #[serde(default)]
+    commands: Vec<String>,
+
+    // searches, most recent first
+    #[serde(default)]
+    searches: Vec<String>,
+}
+
+impl PromptHistory {
+    // load prompt history, using none if missing
+    pub fn load() -> Self {
+        state::load_toml("prompts.toml")
+    }
+
+    // save prompt history
+    fn save(&self) -> Result<()> {
+        state::save_toml("prompts.toml", self)
+    }
+
+    // get entries for prompt
+    pub fn entries(&mut self, kind: PromptKind) -> &mut Vec<String> {
+        match kind {
+            PromptKind::Command => &mut self.commands,
+            PromptKind::Search  => &mut self.searches,
+        }
+    }
+
+    // add input as most recent entry
+    pub fn add(kind: PromptKind, input: &str) {
+        let mut history = Self::load();
+        let entries = history.entries(kind);
+
+        entries.retain(|it| it != input);
+        entries.insert(0, String::from(input));
+        entries.truncate(MAX_ENTRIES);
+
+        let _ = history.save();
+    }
+}
+
+// prompt text with cursor
+#[derive(Default)]
+pub struct PromptInput {
+    // typed text
+    pub text: String,
+
+    // cursor byte offset
+    pub pos: usize,
+}
+
+impl PromptInput {
+    // replace text, moving cursor to end
+    pub fn set(&mut self, text: &str) {
+        self.text = String::from(text);
+        self.pos  = self.text.len();
+    }
+
+    // insert text at cursor
+    pub fn insert_str(&mut self, text: &str) {
+        self.text.insert_str(self.pos, text);
+        self.pos += text.len();
+    }
+
+    // insert pasted text on one line, keeping within max length
+    pub fn paste(&mut self, text: &str, max_len: usize) {
+        let mut room = max_len.saturating_sub(self.text.len());
+
+        let text: String = text
+            .lines()
+            .collect::<Vec<&str>>()
+            .join(" ")
+            .chars()
+            // drop control characters besides tabs
+            .filter(|chr| *chr == '\t' || !chr.is_control())
+            .take_while(|chr| match room.checked_sub(chr.len_utf8()) {
+                Some(left) => { room = left; true }
+                None => false,
+            })
+            .collect();
+
+        self.insert_str(&text);
+    }
+
+    // delete char before cursor
+    pub fn delete_char(&mut self) {
+        if let Some(chr) = self.text[..self.pos].chars().next_back() {
+            self.pos -= chr.len_utf8();
+            self.text.remove(self.pos);
+        }
+    }
+
+    // delete word before cursor
+    pub fn delete_word(&mut self) {
+        let start = self.text[..self.pos]
+            .trim_end()
+            .trim_end_matches(|chr: char| !chr.is_whitespace())
+            .len();
+
+        self.text.replace_range(start..self.pos, "");
+        self.pos = start;
+    }
+
+    // move cursor one char left
+    pub fn move_left(&mut self) {
+        if let Some(chr) = self.text[..self.pos].chars().next_back() {
+            self.pos -= chr.len_utf8();
+        }
+    }
+
+    // move cursor one char right
+    pub fn move_right(&mut self) {
+        if let Some(chr) = self.text[self.pos..].chars().next() {
+            self.pos += chr.len_utf8();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamps_pasted_text() {
+        let mut input = PromptInput::default();
+        input.set("ab");
+
+        input.paste("c\x1b[2J\nd", 7);
+        assert_eq!(input.text, "abc[2J ");
+
+        input.paste("e", 7);
+        assert_eq!(input.text, "abc[2J ");
+    }
+}
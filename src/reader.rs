@@ -1,20 +1,55 @@
-use crossterm::{event, Result};
-use crossterm::event::{Event, KeyEvent};
+use crossterm::event;
+use crossterm::event::{Event, KeyEvent, KeyEventKind};
 
+use std::io::Result;
 use std::time::Duration;
 
+// time waited for input before timer events
+const TICK: Duration = Duration::from_millis(500);
+
+// input for editor loop
+pub enum Input {
+    // key pressed
+    Key(KeyEvent),
+
+    // terminal lost focus
+    FocusLost,
+
+    // text pasted into terminal
+    Paste(String),
+
+    // no input within tick
+    Tick,
+}
+
 pub struct Reader;
 
 impl Reader {
     // read key from stdin
     pub fn read_key() -> Result<KeyEvent> {
         loop {
-            // poll if keypress occurs within duration
-            if event::poll(Duration::from_millis(500))? {
-                if let Event::Key(event) = event::read()? {
-                    return Ok(event);
-                }
+            if let Input::Key(key) = Self::read_input()? {
+                return Ok(key);
             }
         }
     }
+
+    // read input, returning tick if none occurs
+    pub fn read_input() -> Result<Input> {
+        // poll if event occurs within duration
+        if !event::poll(TICK)? {
+            return Ok(Input::Tick);
+        }
+
+        match event::read()? {
+            // ignore key releases
+            Event::Key(key) if key.kind != KeyEventKind::Release => Ok(Input::Key(key)),
+
+            Event::FocusLost => Ok(Input::FocusLost),
+
+            Event::Paste(text) => Ok(Input::Paste(text)),
+
+            _ => Ok(Input::Tick),
+        }
+    }
 }
@@ -0,0 +1,66 @@
+use crate::state;
+
+use serde::{Deserialize, Serialize};
+use shellexpand::tilde;
+
+use std::io::Result;
+use std::path::{self, Path, PathBuf};
+
+// maximum files remembered
+const MAX_FILES: usize = 100;
+
+// recently opened files
+#[derive(Serialize, Deserialize, Default)]
+pub struct Recent {
+    // files, most recent first
+    #[serde(default)]
+    files: Vec<PathBuf>,
+}
+
+impl Recent {
+    // load recent files, using none if missing
+    pub fn load() -> Self {
+        state::load_toml("recent.toml")
+    }
+
+    // save recent files
+    fn save(&self) -> Result<()> {
+        state::save_toml("recent.toml", self)
+    }
+
+    // add file as most recently opened
+    pub fn add(path: &Path) {
+        let path = match path::absolute(path) {
+            Ok(path) => path,
+            Err(_) => return,
+        };
+
+        let mut recent = Self::load();
+
+        recent.files.retain(|it| *it != path);
+        recent.files.insert(0, path);
+        recent.files.truncate(MAX_FILES);
+
+        let _ = recent.save();
+    }
+
+    // get recent files that still exist
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.files
+            .iter()
+            .filter(|path| path.exists())
+            .cloned()
+            .collect()
+    }
+
+    // shorten path for display
+    pub fn display(path: &Path) -> String {
+        let home = tilde("~");
+        let path = path.to_string_lossy();
+
+        match path.strip_prefix(&*home) {
+            Some(rest) if rest.starts_with('/') => format!("~{}", rest),
+            _ => path.into_owned(),
+        }
+    }
+}
@@ -1,64 +1,159 @@
 use crate::buffer::Buffer;
-use crate::config::Config;
-use crate::syntax::{SyntaxHighlight, HighlightType};
+use crate::config::{Config, LineEnding};
+use crate::encoding::Encoding;
+use crate::syntax::{SyntaxHighlight, HighlightType, BRACKETS};
+use crate::state;
 
 use std::fs;
-use std::path::PathBuf;
+use std::ops::Range;
+use std::time::SystemTime;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
 use std::io::{Write, Error, ErrorKind, Result};
 
+// maximum symlinks followed on save
+const MAX_LINKS: usize = 40;
+
+// shown for control characters instead of sending them to terminal
+pub const CONTROL_CHR: char = '\u{FFFD}';
+
+// fnv-1a hash parameters
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME:  u64 = 0x100000001b3;
+
 pub struct Rows {
     // file rows
     pub rows: Vec<Row>,
 
     // filepath
     pub filepath: Option<PathBuf>,
+
+    // encoding used on save
+    pub encoding: Encoding,
+
+    // file contents are binary
+    pub binary: bool,
+
+    // file uses crlf line endings
+    pub crlf: bool,
+
+    // file ends with newline
+    pub final_newline: bool,
+
+    // file state at last load or save
+    pub stamp: Option<DiskStamp>,
+}
+
+// file state on disk for detecting changes
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct DiskStamp {
+    // modification time
+    modified: Option<SystemTime>,
+
+    // size in bytes
+    len: u64,
+
+    // hash of contents
+    hash: u64,
+}
+
+impl DiskStamp {
+    // create stamp from file metadata and contents
+    fn new(metadata: &fs::Metadata, bytes: &[u8]) -> Self {
+        Self {
+            modified: metadata.modified().ok(),
+            len:      metadata.len(),
+            hash:     Self::hash(bytes),
+        }
+    }
+
+    // get key identifying contents across runs
+    pub fn contents_key(&self) -> String {
+        format!("{:x}-{:016x}", self.len, self.hash)
+    }
+
+    // hash file contents with fnv-1a, stable between builds
+    fn hash(bytes: &[u8]) -> u64 {
+        bytes.iter().fold(FNV_OFFSET, |hash, &byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        })
+    }
 }
 
 impl Rows {
     // create rows
-    pub fn new(file: Option<String>, syntax: &mut Option<Box<dyn SyntaxHighlight>>) -> Self {
+    pub fn new(file: Option<String>, syntax: &mut Option<Box<dyn SyntaxHighlight>>) -> Result<Self> {
         match file {
-            None => Self { rows: Vec::new(), filepath: None },
+            None => Ok(Self::empty(None)),
 
             Some(f) => {
+                let path = PathBuf::from(f);
+
                 // check if file exists
-                if PathBuf::from(&f).exists() {
-                    Self::from_file(f.into(), syntax)
+                if path.exists() {
+                    Self::from_file(path, syntax)
                 } else {
-                    Self { rows: Vec::new(), filepath: None }
+                    // keep path for new file
+                    *syntax = Buffer::syntax_for_path(&path);
+                    Ok(Self::empty(Some(path)))
                 }
             }
         }
     }
 
+    // create empty rows
+    fn empty(filepath: Option<PathBuf>) -> Self {
+        Self {
+            rows:     Vec::new(),
+            filepath,
+            encoding: Encoding::Utf8,
+            binary:   false,
+
+            crlf:          false,
+            final_newline: true,
+            stamp:         None,
+        }
+    }
+
     // load rows from file
-    fn from_file(file: PathBuf, syntax: &mut Option<Box<dyn SyntaxHighlight>>) -> Self {
-        let contents = fs::read_to_string(&file)
-            .unwrap_or_else(|_| panic!(
-                "unable to read file `{}`",
-                file.display(),
-            ));
+    fn from_file(file: PathBuf, syntax: &mut Option<Box<dyn SyntaxHighlight>>) -> Result<Self> {
+        let bytes = fs::read(&file)?;
+        let stamp = DiskStamp::new(&fs::metadata(&file)?, &bytes);
 
-        let mut rows = Vec::new();
+        // decode with detected encoding
+        let encoding = Encoding::detect(&bytes);
+        let binary   = encoding.is_binary(&bytes);
+        let contents = encoding.decode(&bytes);
 
-        file.extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| {
-                Buffer::get_syntax(ext)
-                    .map(|syn| syntax.insert(syn))
-            });
+        // detect line ending from first line
+        let crlf = contents
+            .find('\n')
+            .is_some_and(|i| contents[..i].ends_with('\r'));
 
-        for (i, line) in contents.lines().enumerate() {
+        let final_newline = contents.ends_with('\n');
+
+        let mut rows = Vec::new();
+        *syntax = Buffer::syntax_for_path(&file);
+
+        for line in contents.lines() {
             let mut row = Row::new(line.into());
             Self::render_row(&mut row);
             rows.push(row);
+        }
 
-            if let Some(it) = syntax {
-                it.update_syntax(i, &mut rows);
-            }
+        if let Some(it) = syntax {
+            it.update_all(&mut rows);
         }
 
-        Self { rows, filepath: Some(file) }
+        Ok(Self {
+            rows,
+            filepath: Some(file),
+            encoding,
+            binary,
+            crlf,
+            final_newline,
+            stamp: Some(stamp),
+        })
     }
 
     // render row
@@ -95,43 +190,286 @@ impl Rows {
                         row.render.push(' ');
                         index += 1
                     }
+                } else if c.is_control() {
+                    row.render.push(CONTROL_CHR);
                 } else {
                     row.render.push(c);
                 }
             });
     }
 
+    // get line ending used on save
+    pub fn line_ending(&self) -> &'static str {
+        match Config::get_config().files.line_ending {
+            LineEnding::Auto =>
+                if self.crlf { "\r\n" }
+                else { "\n" },
+
+            LineEnding::Lf   => "\n",
+            LineEnding::Crlf => "\r\n",
+        }
+    }
+
+    // check if final newline is written on save
+    pub fn has_final_newline(&self) -> bool {
+        self.final_newline || Config::get_config().files.ensure_final_newline
+    }
+
+    // join lines as written on save
+    pub fn join_lines<'a>(&self, lines: impl IntoIterator<Item = &'a str>) -> String {
+        let ending = self.line_ending();
+
+        let mut text = lines
+            .into_iter()
+            .map(|line| format!("{}{}", line, ending))
+            .collect::<String>();
+
+        // drop ending after last line
+        if !self.has_final_newline() {
+            text.truncate(text.len().saturating_sub(ending.len()));
+        }
+
+        text
+    }
+
+    // check if file changed on disk since last load or save
+    pub fn changed_on_disk(&mut self) -> bool {
+        let path = match &self.filepath {
+            Some(path) => path,
+            None => return false,
+        };
+
+        // deleted files are written again on save
+        let metadata = match fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(_) => return false,
+        };
+
+        let stamp = match &mut self.stamp {
+            Some(stamp) => stamp,
+
+            // file created since opening
+            None => return true,
+        };
+
+        if stamp.modified == metadata.modified().ok() && stamp.len == metadata.len() {
+            return false;
+        }
+
+        // compare contents when only metadata changed
+        match fs::read(path) {
+            Ok(bytes) => {
+                let new_stamp = DiskStamp::new(&metadata, &bytes);
+
+                // keep stamp until changed contents are reloaded or saved over
+                if new_stamp.hash != stamp.hash {
+                    return true;
+                }
+
+                *stamp = new_stamp;
+                false
+            }
+
+            Err(_) => false,
+        }
+    }
+
+    // get unified diff between file on disk and rows
+    pub fn diff_with_disk(&self) -> Result<String> {
+        let path = self.filepath
+            .as_ref()
+            .ok_or_else(|| Error::other("no file name specified"))?;
+
+        Self::diff_file(path, &self.join_lines(self.rows.iter().map(|it| it.content.as_str())))
+    }
+
+    // get unified diff between decoded file and text
+    pub fn diff_file(path: &Path, text: &str) -> Result<String> {
+        let bytes = fs::read(path)?;
+        let disk  = Encoding::detect(&bytes).decode(&bytes);
+
+        // decoded copy of file to diff against, kept in private state directory
+        let (copy, mut file) = state::create_temp(&state::state_dir("diff")?, "diff")?;
+
+        let label = path.display().to_string();
+
+        let output = file
+            .write_all(disk.as_bytes())
+            .and_then(|_| Self::run_diff(&copy, &label, text));
+
+        let _ = fs::remove_file(&copy);
+
+        output
+    }
+
+    // run diff between file and text, labelling both sides
+    fn run_diff(file: &Path, label: &str, text: &str) -> Result<String> {
+        let mut child = Command::new("diff")
+            .arg("-u")
+            .args(["--label", label, "--label", label])
+            .arg(file)
+            .arg("-")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| match err.kind() {
+                ErrorKind::NotFound => Error::other("`diff` command not found in PATH"),
+                _ => err,
+            })?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+
+        let output = child.wait_with_output()?;
+
+        // diff exits with 2 on trouble
+        if output.status.code() == Some(2) {
+            return Err(Error::other(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    // get bytes as written on save
+    pub fn encoded(&self) -> Result<Vec<u8>> {
+        // keep or add final newline
+        let contents = self.join_lines(self.rows.iter().map(|it| it.content.as_str()));
+
+        self.encoding.encode(&contents)
+    }
+
     // write to disk
-    pub fn write_file(&self) -> Result<usize> {
+    pub fn write_file(&mut self) -> Result<usize> {
         match &self.filepath {
             None => {
-                Err(Error::new(
-                    ErrorKind::Other,
-                    "no file name specified",
-                ))
+                Err(Error::other("no file name specified"))
             }
 
             Some(name) => {
-                let mut file = fs::OpenOptions::new()
-                    .write(true)
-                    .create(true)
-                    .open(name)?;
-
-                let contents = self
-                    .rows
-                    .iter()
-                    .map(|it| it.content.as_str())
-                    .collect::<Vec<&str>>()
-                    .join("\n");
-
-                file.set_len(contents.len() as u64)?;
-                file.write_all(contents.as_bytes())?;
-
-                Ok(contents.as_bytes().len())
+                // create missing parent directories
+                if let Some(parent) = name.parent() {
+                    if !parent.as_os_str().is_empty() && !parent.exists() {
+                        if !Config::get_config().files.create_dirs {
+                            return Err(Error::other(format!(
+                                "directory `{}` does not exist",
+                                parent.display(),
+                            )));
+                        }
+
+                        fs::create_dir_all(parent)?;
+                    }
+                }
+
+                let bytes = self.encoded()?;
+
+                // write through symlinks to their target
+                let target = Self::resolve_link(name);
+                Self::write_atomic(&target, &bytes)?;
+
+                self.stamp = fs::metadata(&target)
+                    .ok()
+                    .map(|metadata| DiskStamp::new(&metadata, &bytes));
+
+                Ok(bytes.len())
             }
         }
     }
 
+    // follow symlinks to file they point to
+    fn resolve_link(path: &Path) -> PathBuf {
+        let mut target = path.to_path_buf();
+
+        // bounded to avoid symlink loops
+        for _ in 0..MAX_LINKS {
+            match fs::read_link(&target) {
+                Ok(link) => {
+                    target = match target.parent() {
+                        Some(parent) => parent.join(link),
+                        None => link,
+                    };
+                }
+
+                Err(_) => break,
+            }
+        }
+
+        target
+    }
+
+    // write bytes to temporary file and rename over target
+    fn write_atomic(target: &Path, bytes: &[u8]) -> Result<()> {
+        let dir = target
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+
+        let name = target
+            .file_name()
+            .ok_or_else(|| Error::other("invalid file name"))?;
+
+        // hidden temporary file in same directory
+        let temp = dir.join(format!(
+            ".{}.{}.tmp",
+            name.to_string_lossy(),
+            process::id(),
+        ));
+
+        let metadata = fs::metadata(target).ok();
+
+        // keep copy of original file
+        if metadata.is_some() && Config::get_config().files.backup {
+            let mut backup = target.as_os_str().to_owned();
+            backup.push("~");
+
+            fs::copy(target, backup)?;
+        }
+
+        if let Err(err) = Self::write_temp(&temp, bytes, metadata.as_ref()) {
+            let _ = fs::remove_file(&temp);
+            return Err(err);
+        }
+
+        if let Err(err) = fs::rename(&temp, target) {
+            let _ = fs::remove_file(&temp);
+            return Err(err);
+        }
+
+        // sync directory so rename survives crash
+        #[cfg(unix)]
+        if let Ok(dir) = fs::File::open(dir) {
+            let _ = dir.sync_all();
+        }
+
+        Ok(())
+    }
+
+    // write and sync temporary file, copying metadata from original
+    fn write_temp(temp: &Path, bytes: &[u8], metadata: Option<&fs::Metadata>) -> Result<()> {
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(temp)?;
+
+        file.write_all(bytes)?;
+
+        if let Some(metadata) = metadata {
+            file.set_permissions(metadata.permissions())?;
+
+            // ownership can only be kept with permission to change it
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::{chown, MetadataExt};
+                let _ = chown(temp, Some(metadata.uid()), Some(metadata.gid()));
+            }
+        }
+
+        file.sync_all()
+    }
+
     // join adjacent rows when deleting
     pub fn join_adjacent_rows(&mut self, at: usize) {
         let curr_row = self.rows.remove(at);
@@ -141,44 +479,40 @@ impl Rows {
         Self::render_row(prev_row);
     }
 
+    // get single level of indentation
+    pub fn indent_unit() -> String {
+        let config = Config::get_config();
+
+        if config.tabs.soft {
+            // use spaces for soft tabs
+            " ".repeat(config.indent.width)
+        } else {
+            String::from("\t")
+        }
+    }
+
+    // get leading whitespace of row
+    pub fn indentation(&self, at: usize) -> &str {
+        let content = self.get_content(at);
+        let len = content.len() - content.trim_start().len();
+
+        &content[..len]
+    }
+
     // auto indent row contents
-    pub fn auto_indent(&self, at: usize, contents: &str) -> String {
+    pub fn auto_indent(&self, at: usize, contents: &str, triggers: &[char]) -> String {
         // indented contents
         let mut indented = String::new();
         let auto_indent = Config::get_config().indent.auto;
 
         if auto_indent && at > 0 {
-            let chars = self
-                .get_row(at - 1)
-                .content
-                .chars();
-
             // get indentation
-            for chr in chars {
-                if chr.is_whitespace() {
-                    indented.push(chr);
-                } else {
-                    break;
-                }
-            }
+            indented.push_str(self.indentation(at - 1));
 
-            if let Some(chr) = self.get_row(at - 1).content.chars().last() {
+            if let Some(chr) = self.get_row(at - 1).content.trim_end().chars().last() {
                 // increase indentation on block open
-                if ['[', '{', '('].contains(&chr) {
-                    let soft_tabs = Config::get_config().tabs.soft;
-
-                    if soft_tabs {
-                        let width = Config::get_config().indent.width;
-
-                        // use spaces for soft tabs
-                        indented.push_str(
-                            &(0..width)
-                                .map(|_| ' ')
-                                .collect::<String>()
-                        );
-                    } else {
-                        indented.push('\t');
-                    }
+                if triggers.contains(&chr) {
+                    indented.push_str(&Self::indent_unit());
                 }
             }
         }
@@ -187,6 +521,82 @@ impl Rows {
         indented
     }
 
+    // get length of single level of indentation at end of row indentation
+    pub fn dedent_len(&self, at: usize) -> usize {
+        let indent = self.indentation(at);
+        let unit = Self::indent_unit();
+
+        if indent.ends_with(&unit) {
+            unit.len()
+        } else if indent.ends_with('\t') {
+            1
+        } else {
+            // remove partial soft tab
+            indent.len() - indent.trim_end_matches(' ').len()
+        }
+    }
+
+    // find bracket matching bracket at position, searching up to max rows
+    pub fn find_match(&self, x: usize, y: usize, max_rows: usize) -> Option<(usize, usize)> {
+        let chr = self.get_content(y).get(x..)?.chars().next()?;
+
+        // get bracket pair and direction
+        let (open, close, forward) = BRACKETS
+            .iter()
+            .find_map(|&(open, close)| {
+                if chr == open {
+                    Some((open, close, true))
+                } else if chr == close {
+                    Some((open, close, false))
+                } else {
+                    None
+                }
+            })?;
+
+        if !self.get_row(y).is_code(x, &self.get_row(y).render_indices()) {
+            return None;
+        }
+
+        let mut depth = 0;
+
+        // rows in search direction
+        let rows: Box<dyn Iterator<Item = usize>> =
+            if forward { Box::new(y..self.num_rows()) }
+            else { Box::new((0..=y).rev()) };
+
+        for j in rows.take(max_rows) {
+            let row = self.get_row(j);
+            let indices = row.render_indices();
+
+            // chars in search direction
+            let chars: Box<dyn Iterator<Item = (usize, char)>> = match (forward, j == y) {
+                (true,  true)  => Box::new(row.content[x..].char_indices().map(|(i, c)| (i + x, c))),
+                (true,  false) => Box::new(row.content.char_indices()),
+                (false, true)  => Box::new(row.content[..=x].char_indices().rev()),
+                (false, false) => Box::new(row.content.char_indices().rev()),
+            };
+
+            for (i, c) in chars {
+                if (c != open && c != close) || !row.is_code(i, &indices) {
+                    continue;
+                }
+
+                // track nesting depth
+                if (c == open) == forward {
+                    depth += 1;
+                } else {
+                    depth -= 1;
+                }
+
+                if depth == 0 {
+                    return Some((i, j));
+                }
+            }
+        }
+
+        None
+    }
+
     // insert new row
     pub fn insert_row(&mut self, at: usize, contents: String) {
         let mut row = Row::new(contents);
@@ -195,6 +605,48 @@ impl Rows {
         self.rows.insert(at, row);
     }
 
+    // replace all rows with contents
+    pub fn set_contents(&mut self, contents: Vec<String>) {
+        self.rows = contents
+            .into_iter()
+            .map(|content| {
+                let mut row = Row::new(content);
+                Self::render_row(&mut row);
+                row
+            })
+            .collect();
+    }
+
+    // get contents of all rows
+    pub fn contents(&self) -> Vec<String> {
+        self.contents_of(0..self.rows.len())
+    }
+
+    // get contents of range of rows
+    pub fn contents_of(&self, range: Range<usize>) -> Vec<String> {
+        self.rows[range]
+            .iter()
+            .map(|row| row.content.clone())
+            .collect()
+    }
+
+    // replace range of rows with contents, returning replaced contents
+    pub fn replace_rows(&mut self, range: Range<usize>, contents: Vec<String>) -> Vec<String> {
+        let rows = contents
+            .into_iter()
+            .map(|content| {
+                let mut row = Row::new(content);
+                Self::render_row(&mut row);
+                row
+            })
+            .collect::<Vec<Row>>();
+
+        self.rows
+            .splice(range, rows)
+            .map(|row| row.content)
+            .collect()
+    }
+
     // number of rows
     pub fn num_rows(&self) -> usize {
         self.rows.len()
@@ -257,4 +709,183 @@ impl Row {
         self.content.remove(at);
         Rows::render_row(self);
     }
+
+    // insert string
+    pub fn insert_str(&mut self, at: usize, string: &str) {
+        self.content.insert_str(at, string);
+        Rows::render_row(self);
+    }
+
+    // delete range of chars
+    pub fn delete_range(&mut self, range: Range<usize>) {
+        self.content.replace_range(range, "");
+        Rows::render_row(self);
+    }
+
+    // check if index is outside of strings and comments
+    pub fn is_code(&self, at: usize, indices: &[usize]) -> bool {
+        !matches!(
+            self.highlight.get(indices[at]),
+            Some(HighlightType::Stringlike | HighlightType::Comment),
+        )
+    }
+
+    // render indices for each content index
+    pub fn render_indices(&self) -> Vec<usize> {
+        // render matches content without tabs or control chars
+        if !self.content.chars().any(char::is_control) {
+            return (0..=self.content.len()).collect();
+        }
+
+        let config   = Config::get_config();
+        let tab_stop = config.tabs.width;
+        let tab_len  = config.tabs.chr.len_utf8();
+
+        let mut indices = Vec::with_capacity(self.content.len() + 1);
+
+        let mut index = 0;
+        let mut len   = 0;
+
+        for chr in self.content.chars() {
+            for _ in 0..chr.len_utf8() {
+                indices.push(len);
+            }
+
+            index += 1;
+
+            // follow tab expansion in render
+            if chr == '\t' {
+                len += tab_len;
+
+                while index % tab_stop != 0 {
+                    len   += 1;
+                    index += 1;
+                }
+            } else if chr.is_control() {
+                len += CONTROL_CHR.len_utf8();
+            } else {
+                len += chr.len_utf8();
+            }
+        }
+
+        indices.push(len);
+        indices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::testing::{temp_file, test_dir};
+
+    // load rows from file
+    fn open(path: &Path) -> Rows {
+        Rows::new(Some(path.to_string_lossy().into_owned()), &mut None).unwrap()
+    }
+
+    // load file, save it again and get written bytes
+    fn resave(name: &str, bytes: &[u8], edit: impl FnOnce(&mut Rows)) -> Vec<u8> {
+        let path = temp_file(name, bytes);
+        let mut rows = open(&path);
+
+        edit(&mut rows);
+        rows.write_file().unwrap();
+
+        let written = fs::read(&path).unwrap();
+        fs::remove_file(path).unwrap();
+
+        written
+    }
+
+    #[test]
+    fn keeps_crlf_line_endings() {
+        let bytes = b"one\r\ntwo\r\n";
+
+        assert_eq!(resave("crlf.txt", bytes, |_| {}), bytes);
+    }
+
+    #[test]
+    fn uses_crlf_for_new_rows() {
+        let written = resave("crlf-edit.txt", b"one\r\ntwo\r\n", |rows| {
+            rows.insert_row(1, String::from("new"));
+        });
+
+        assert_eq!(written, b"one\r\nnew\r\ntwo\r\n");
+    }
+
+    #[test]
+    fn keeps_missing_final_newline() {
+        assert_eq!(resave("noeol.txt", b"one\ntwo", |_| {}), b"one\ntwo");
+        assert_eq!(resave("noeol-crlf.txt", b"one\r\ntwo", |_| {}), b"one\r\ntwo");
+    }
+
+    #[test]
+    fn keeps_final_newline() {
+        assert_eq!(resave("eol.txt", b"one\ntwo\n", |_| {}), b"one\ntwo\n");
+    }
+
+    #[test]
+    fn keeps_encoding_with_crlf() {
+        let bytes = Encoding::Utf16Le.encode("café\r\n☕\r\n").unwrap();
+
+        assert_eq!(resave("utf16.txt", &bytes, |_| {}), bytes);
+    }
+
+    #[test]
+    fn reports_disk_changes_until_saved() {
+        let path = temp_file("changed.txt", b"one\n");
+        let mut rows = open(&path);
+
+        fs::write(&path, b"other\n").unwrap();
+
+        assert!(rows.changed_on_disk());
+        assert!(rows.changed_on_disk());
+
+        rows.write_file().unwrap();
+        assert!(!rows.changed_on_disk());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn diffs_decoded_file() {
+        let bytes = Encoding::Utf16Le.encode("one\r\ntwo\r\n").unwrap();
+
+        let path = temp_file("diff.txt", &bytes);
+        let mut rows = open(&path);
+
+        assert_eq!(rows.diff_with_disk().unwrap(), "");
+
+        rows.insert_row(2, String::from("three"));
+        assert!(rows.diff_with_disk().unwrap().contains("+three\r\n"));
+
+        // decoded copies removed after diffing
+        let copies = fs::read_dir(state::state_dir("diff").unwrap()).unwrap();
+        assert_eq!(copies.count(), 0);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn keys_contents_by_length_and_hash() {
+        let path = temp_file("key.txt", b"a");
+        let rows = open(&path);
+
+        // fnv-1a of "a"
+        assert_eq!(rows.stamp.unwrap().contents_key(), "1-af63dc4c8601ec8c");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn renders_control_chars_as_replacement() {
+        test_dir();
+
+        let mut row = Row::new(String::from("a\x1b[2Jb\u{9b}"));
+        Rows::render_row(&mut row);
+
+        assert_eq!(row.render, "a\u{FFFD}[2Jb\u{FFFD}");
+        assert_eq!(row.render_indices()[2], 1 + CONTROL_CHR.len_utf8());
+    }
 }
@@ -0,0 +1,80 @@
+use crate::state;
+
+use serde::{Deserialize, Serialize};
+
+use std::env;
+use std::fs;
+use std::io::{Error, Result};
+use std::path::PathBuf;
+
+// open buffers saved between runs
+#[derive(Serialize, Deserialize, Default)]
+pub struct Session {
+    // index of current buffer
+    #[serde(default)]
+    pub current: usize,
+
+    // buffers with file paths
+    #[serde(default)]
+    pub buffers: Vec<SessionBuffer>,
+}
+
+// buffer saved in session
+#[derive(Serialize, Deserialize)]
+pub struct SessionBuffer {
+    // absolute file path
+    pub path: PathBuf,
+
+    // cursor position
+    pub cursor: (usize, usize),
+
+    // row and column offsets
+    pub offset: (usize, usize),
+}
+
+impl Session {
+    // get file for named session
+    fn named_path(name: &str) -> Result<PathBuf> {
+        if name.is_empty() || name.contains('/') || name.starts_with('.') {
+            return Err(Error::other(format!("invalid session name `{}`", name)));
+        }
+
+        Ok(state::state_dir("sessions")?.join(format!("{}.toml", name)))
+    }
+
+    // get file for working directory session
+    fn dir_path() -> Result<PathBuf> {
+        let dir = env::current_dir()?;
+        Ok(state::state_dir("sessions/dirs")?.join(format!("{}.toml", state::escape_path(&dir))))
+    }
+
+    // read session from file
+    fn read(path: PathBuf) -> Result<Self> {
+        toml::from_str(&fs::read_to_string(path)?).map_err(Error::other)
+    }
+
+    // write session to file
+    fn write(&self, path: PathBuf) -> Result<()> {
+        fs::write(path, toml::to_string(self).map_err(Error::other)?)
+    }
+
+    // load named session
+    pub fn load(name: &str) -> Result<Self> {
+        Self::read(Self::named_path(name)?)
+    }
+
+    // save named session
+    pub fn save(&self, name: &str) -> Result<()> {
+        self.write(Self::named_path(name)?)
+    }
+
+    // load session for working directory
+    pub fn load_dir() -> Result<Self> {
+        Self::read(Self::dir_path()?)
+    }
+
+    // save session for working directory
+    pub fn save_dir(&self) -> Result<()> {
+        self.write(Self::dir_path()?)
+    }
+}
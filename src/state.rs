@@ -0,0 +1,136 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use shellexpand::tilde;
+
+use std::env;
+use std::process;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::io::{Write, Error, ErrorKind, Result};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// id given to next temporary file
+static NEXT_TEMP: AtomicUsize = AtomicUsize::new(1);
+
+// get state directory, creating it if missing
+pub fn state_dir(name: &str) -> Result<PathBuf> {
+    // prefer xdg state directory
+    let base = env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(&*tilde("~/.local/state")));
+
+    let dir = base.join("ferrite").join(name);
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+
+    // only accessible by owner
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+
+    builder.create(&dir)?;
+
+    Ok(dir)
+}
+
+// get file in state directory
+pub fn state_file(name: &str) -> Result<PathBuf> {
+    Ok(state_dir("")?.join(name))
+}
+
+// create new file only readable by owner, never following existing files
+pub fn create_private(path: &Path) -> Result<File> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    options.open(path)
+}
+
+// create uniquely named private file in directory
+pub fn create_temp(dir: &Path, name: &str) -> Result<(PathBuf, File)> {
+    loop {
+        let path = dir.join(format!(
+            ".{}.{}.{}.tmp",
+            name,
+            process::id(),
+            NEXT_TEMP.fetch_add(1, Ordering::Relaxed),
+        ));
+
+        // skip names left by other processes
+        match create_private(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+// replace file with private copy of contents, never leaving partial writes
+pub fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    let dir = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+
+    let name = path
+        .file_name()
+        .ok_or_else(|| Error::other("invalid file name"))?;
+
+    let (temp, mut file) = create_temp(dir, &name.to_string_lossy())?;
+
+    let written = file
+        .write_all(contents)
+        .and_then(|_| file.sync_all())
+        .and_then(|_| fs::rename(&temp, path));
+
+    if written.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+
+    written
+}
+
+// load toml state file, using default if missing or invalid
+pub fn load_toml<T: DeserializeOwned + Default>(name: &str) -> T {
+    state_file(name)
+        .and_then(fs::read_to_string)
+        .ok()
+        .and_then(|contents| toml::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+// save toml state file
+pub fn save_toml<T: Serialize>(name: &str, value: &T) -> Result<()> {
+    fs::write(state_file(name)?, toml::to_string(value).map_err(Error::other)?)
+}
+
+// get file name for state about path
+pub fn escape_path(path: &Path) -> String {
+    // use absolute path so names are unique
+    let path = env::current_dir()
+        .map(|dir| dir.join(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+
+    // escape percent first so names map back to one path
+    path.to_string_lossy().replace('%', "%25").replace('/', "%2F")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_paths_uniquely() {
+        assert_ne!(escape_path(Path::new("/a/%")), escape_path(Path::new("/a%/")));
+        assert_eq!(escape_path(Path::new("/a%/b")), "%2Fa%25%2Fb");
+    }
+}
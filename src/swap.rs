@@ -0,0 +1,199 @@
+use crate::config::Config;
+use crate::rows::Rows;
+use crate::state;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::time::{Duration, Instant};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// id given to next swap
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+// first line of swap, followed by pid of writing process
+const HEADER: &str = "ferrite swap ";
+
+// swap left by another process
+pub struct Stale {
+    // process that wrote swap
+    pub pid: Option<u32>,
+
+    // buffer contents
+    pub contents: String,
+}
+
+impl Stale {
+    // read swap file, keeping contents of swaps without header
+    fn read(path: &Path) -> Option<Self> {
+        let text = fs::read_to_string(path).ok()?;
+
+        let parsed = text.strip_prefix(HEADER).and_then(|rest| {
+            let (pid, contents) = rest.split_once('\n')?;
+            Some((pid.parse().ok()?, contents))
+        });
+
+        Some(match parsed {
+            Some((pid, contents)) => Self { pid: Some(pid), contents: contents.to_string() },
+            None => Self { pid: None, contents: text },
+        })
+    }
+
+    // check if writing process is still running
+    pub fn running(&self) -> bool {
+        // signal 0 only checks that process exists
+        self.pid.is_some_and(|pid| {
+            Command::new("kill")
+                .args(["-0", &pid.to_string()])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success())
+        })
+    }
+}
+
+// recovery file for unsaved changes
+pub struct Swap {
+    // swap file last written
+    path: Option<PathBuf>,
+
+    // dirty status when last written
+    written: u64,
+
+    // time of last write
+    last: Instant,
+
+    // id naming swap of unnamed buffer
+    id: usize,
+}
+
+impl Swap {
+    // create swap with nothing written
+    pub fn new() -> Self {
+        Self {
+            path:    None,
+            written: 0,
+            last:    Instant::now(),
+            id:      NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    // get swap file for file path
+    pub fn path_for(filepath: &Path) -> Option<PathBuf> {
+        let dir = state::state_dir("swap").ok()?;
+        Some(dir.join(format!("{}.swp", state::escape_path(filepath))))
+    }
+
+    // get swap file for unnamed buffer
+    fn unnamed_path(&self) -> Option<PathBuf> {
+        let dir = state::state_dir("swap").ok()?;
+        Some(dir.join(format!("unnamed-{}-{}.swp", process::id(), self.id)))
+    }
+
+    // read swap left for file path by another process
+    pub fn read_stale(filepath: &Path) -> Option<Stale> {
+        Stale::read(&Self::path_for(filepath)?)
+            .filter(|stale| stale.pid != Some(process::id()))
+    }
+
+    // read swaps of unnamed buffers left by exited processes
+    pub fn unnamed_stale() -> Vec<(PathBuf, Stale)> {
+        let entries = match state::state_dir("swap").and_then(fs::read_dir) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy())
+                    .is_some_and(|name| name.starts_with("unnamed-") && name.ends_with(".swp"))
+            })
+            .collect();
+
+        paths.sort();
+
+        paths
+            .into_iter()
+            .filter_map(|path| Stale::read(&path).map(|stale| (path, stale)))
+            .filter(|(_, stale)| !stale.running())
+            .collect()
+    }
+
+    // remove swap left for file path
+    pub fn remove_stale(filepath: &Path) {
+        if let Some(path) = Self::path_for(filepath) {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    // write swap if buffer changed since last write
+    pub fn update(&mut self, rows: &Rows, dirty: u64) {
+        let config = &Config::get_config().swap;
+
+        // clean buffers need no recovery
+        if !config.enabled || dirty == 0 {
+            return self.remove();
+        }
+
+        let path = match rows.filepath.as_deref() {
+            Some(filepath) => Self::path_for(filepath),
+            None => self.unnamed_path(),
+        };
+
+        let path = match path {
+            Some(path) => path,
+            None => return,
+        };
+
+        // remove swap for previous file name
+        if self.path.as_ref().is_some_and(|old| *old != path) {
+            self.remove();
+        }
+
+        let interval = Duration::from_secs(config.interval);
+
+        if dirty == self.written || self.last.elapsed() < interval {
+            return;
+        }
+
+        let contents = format!("{}{}\n{}", HEADER, process::id(), rows.contents().join("\n"));
+
+        if state::write_private(&path, contents.as_bytes()).is_ok() {
+            self.path    = Some(path);
+            self.written = dirty;
+            self.last    = Instant::now();
+        }
+    }
+
+    // remove written swap file
+    pub fn remove(&mut self) {
+        if let Some(path) = self.path.take() {
+            let _ = fs::remove_file(path);
+        }
+
+        self.written = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::testing::temp_file;
+
+    #[test]
+    fn reads_pid_from_swap_header() {
+        let stale = Stale::read(&temp_file("header.swp", b"ferrite swap 42\none\ntwo")).unwrap();
+        assert_eq!(stale.pid, Some(42));
+        assert_eq!(stale.contents, "one\ntwo");
+
+        // swaps without header keep all contents
+        let stale = Stale::read(&temp_file("plain.swp", b"one\ntwo")).unwrap();
+        assert_eq!(stale.pid, None);
+        assert_eq!(stale.contents, "one\ntwo");
+    }
+}
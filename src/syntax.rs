@@ -1,10 +1,15 @@
 use crate::contents::Contents;
 use crate::rows::Row;
 
-use crossterm::{queue, Result};
-use crossterm::style::{Color, ResetColor, SetForegroundColor};
+use crossterm::queue;
+use crossterm::style::{Attribute, Color, ResetColor, SetAttribute, SetForegroundColor};
 
 use std::cmp::min;
+use std::ops::Range;
+use std::io::Result;
+
+// bracket pairs
+pub const BRACKETS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
 
 #[derive(Clone, Copy)]
 // highlight type
@@ -12,11 +17,65 @@ pub enum HighlightType {
     Normal,
     Number,
     SearchMatch,
+    MatchBracket,
+    Selection,
     Stringlike,
     Comment,
     Other(Color),
 }
 
+// edit made to rows
+#[derive(Clone, Copy)]
+#[cfg_attr(not(feature = "tree-sitter"), allow(dead_code))]
+pub struct RowsEdit {
+    // start position of edit
+    pub start: (usize, usize),
+
+    // end position before edit
+    pub old_end: (usize, usize),
+
+    // end position after edit
+    pub new_end: (usize, usize),
+
+    // bytes removed and inserted
+    pub old_len: usize,
+    pub new_len: usize,
+}
+
+impl RowsEdit {
+    // edit inserting text at position
+    pub fn insert(start: (usize, usize), new_end: (usize, usize), len: usize) -> Self {
+        Self {
+            start,
+            old_end: start,
+            new_end,
+            old_len: 0,
+            new_len: len,
+        }
+    }
+
+    // edit removing text from position
+    pub fn delete(start: (usize, usize), old_end: (usize, usize), len: usize) -> Self {
+        Self {
+            start,
+            old_end,
+            new_end: start,
+            old_len: len,
+            new_len: 0,
+        }
+    }
+}
+
+// check if char is separator
+pub fn is_separator(c: char) -> bool {
+    c.is_whitespace() || [
+        ',', '.', ';', '(', ')', '[', ']',
+        '{', '}', '+', '-', '/', '*', '=',
+        '~', '%', '<', '>', '&', ':', '|',
+        '"', '\'',
+    ].contains(&c)
+}
+
 // syntax highlighting
 pub trait SyntaxHighlight {
     // file extensions for syntax
@@ -34,12 +93,30 @@ pub trait SyntaxHighlight {
     // strings for starting and ending multiline comments
     fn multiline_comment(&self) -> Option<(&str, &str)>;
 
+    // chars increasing indentation at end of row
+    fn indent_triggers(&self) -> &[char];
+
     // convert to crossterm color
     fn syntax_color(&self, highlight: &HighlightType) -> Color;
 
     // update syntax for row
     fn update_syntax(&self, at: usize, rows: &mut Vec<Row>);
 
+    // update syntax for range of rows
+    fn update_rows(&self, range: Range<usize>, rows: &mut Vec<Row>) {
+        for i in range {
+            self.update_syntax(i, rows);
+        }
+    }
+
+    // update syntax for all rows
+    fn update_all(&self, rows: &mut Vec<Row>) {
+        self.update_rows(0..rows.len(), rows);
+    }
+
+    // track edit for incremental highlighting
+    fn edit_rows(&self, _rows: &[Row], _edit: RowsEdit) {}
+
     // apply row highlighting
     fn color_row(
         &self,
@@ -59,6 +136,15 @@ pub trait SyntaxHighlight {
         ));
 
         for (idx, chr) in render.chars().enumerate() {
+            // reverse matching brackets and selection
+            if let HighlightType::MatchBracket | HighlightType::Selection = highlight[idx] {
+                queue!(contents, SetAttribute(Attribute::Reverse))?;
+                contents.push(chr);
+                queue!(contents, SetAttribute(Attribute::NoReverse))?;
+
+                continue;
+            }
+
             let color = self.syntax_color(&highlight[idx]);
 
             // set fg color if not the current color
@@ -76,14 +162,18 @@ pub trait SyntaxHighlight {
         Ok(())
     }
 
+    // pairs for auto closing
+    fn pairs(&self) -> Vec<(char, char)> {
+        BRACKETS
+            .iter()
+            .copied()
+            .chain(self.stringlikes().iter().map(|&chr| (chr, chr)))
+            .collect()
+    }
+
     // check if char is separator
     fn is_separator(&self, c: char) -> bool {
-        c.is_whitespace() || [
-            ',', '.', ';', '(', ')', '[', ']',
-            '{', '}', '+', '-', '/', '*', '=',
-            '~', '%', '<', '>', '&', ':', '|',
-            '"', '\'',
-        ].contains(&c)
+        is_separator(c)
     }
 }
 
@@ -95,6 +185,7 @@ syntax_struct! {
         stringlikes: &['"', '\''],
         comment: "//",
         multiline_comment: Some(("/*", "*/")),
+        indent: &['{', '[', '('],
         keywords: {
             // words
             Color::Blue => [
@@ -136,6 +227,7 @@ syntax_struct! {
         stringlikes: &['"', '\'', '`'],
         comment: "//",
         multiline_comment: Some(("/*", "*/")),
+        indent: &['{', '[', '('],
         keywords: {
             // words
             Color::Blue => [
@@ -167,6 +259,42 @@ syntax_struct! {
     }
 }
 
+// python syntax
+syntax_struct! {
+    struct PythonHighlight {
+        extensions: ["py"],
+        filetype: "python",
+        stringlikes: &['"', '\''],
+        comment: "#",
+        multiline_comment: None,
+        indent: &[':', '{', '[', '('],
+        keywords: {
+            // words
+            Color::Blue => [
+                "and",    "as",     "assert", "async",  "await",    "break",
+                "class",  "continue", "def",  "del",    "elif",     "else",
+                "except", "finally", "for",   "from",   "global",   "if",
+                "import", "in",     "is",     "lambda", "nonlocal", "not",
+                "or",     "pass",   "raise",  "return", "try",      "while",
+                "with",   "yield",
+            ],
+
+            // values
+            Color::Red => [
+                "True", "False", "None",
+            ],
+
+            // operators
+            Color::Magenta => [
+                "==", "!=", "<=", "<",
+                ">=", ">",  "->", "+=",
+                "-=", "*=", "/=", "=",
+                "self",
+            ],
+        },
+    }
+}
+
 // create struct implementing SyntaxHighlight
 macro_rules! syntax_struct {
     (
@@ -176,6 +304,7 @@ macro_rules! syntax_struct {
             stringlikes: $strs:expr,
             comment: $cmt:expr,
             multiline_comment: $ml_cmt:expr,
+            indent: $indent:expr,
             keywords: {
                 $($color:expr => [
                     $($word:expr),*
@@ -201,6 +330,9 @@ macro_rules! syntax_struct {
 
             // starting and ending string for multiline comments
             multiline_comment: Option<(&'static str, &'static str)>,
+
+            // chars increasing indentation
+            indent: &'static [char],
         }
 
         impl $Name {
@@ -212,6 +344,7 @@ macro_rules! syntax_struct {
                     stringlikes: $strs,
                     comment: $cmt,
                     multiline_comment: $ml_cmt,
+                    indent: $indent,
                 }
             }
         }
@@ -237,11 +370,17 @@ macro_rules! syntax_struct {
                 self.multiline_comment
             }
 
+            fn indent_triggers(&self) -> &[char] {
+                self.indent
+            }
+
             fn syntax_color(&self, highlight: &HighlightType) -> Color {
                 match highlight {
                     HighlightType::Normal       => Color::Reset,
                     HighlightType::Number       => Color::Cyan,
                     HighlightType::SearchMatch  => Color::Yellow,
+                    HighlightType::MatchBracket => Color::Reset,
+                    HighlightType::Selection    => Color::Reset,
                     HighlightType::Stringlike   => Color::Green,
                     HighlightType::Comment      => Color::DarkGrey,
                     HighlightType::Other(color) => *color,
@@ -0,0 +1,31 @@
+use std::env;
+use std::fs;
+use std::process;
+use std::sync::Once;
+use std::path::PathBuf;
+
+// home and state directories pointed at test directory
+static SETUP: Once = Once::new();
+
+// get directory for test files, keeping user config and state out of tests
+pub fn test_dir() -> PathBuf {
+    let dir = env::temp_dir().join(format!("ferrite-test-{}", process::id()));
+
+    SETUP.call_once(|| {
+        fs::create_dir_all(dir.join("home")).unwrap();
+
+        // default config without ~/.ferrite.toml
+        env::set_var("HOME", dir.join("home"));
+        env::set_var("XDG_STATE_HOME", dir.join("state"));
+    });
+
+    dir
+}
+
+// write bytes to fresh file in test directory
+pub fn temp_file(name: &str, bytes: &[u8]) -> PathBuf {
+    let path = test_dir().join(name);
+    fs::write(&path, bytes).unwrap();
+
+    path
+}
@@ -0,0 +1,282 @@
+use crate::syntax::*;
+use crate::rows::Row;
+
+use crossterm::style::Color;
+use tree_sitter::{InputEdit, Language, Parser, Point, Query, QueryCursor, Tree};
+
+use std::ops::Range;
+use std::cell::{Cell, RefCell};
+
+// tree-sitter syntax highlighting
+pub struct TreeSitterHighlight {
+    // highlighter used without a parse
+    fallback: Box<dyn SyntaxHighlight>,
+
+    // highlight query for grammar
+    query: Query,
+
+    // parser for grammar
+    parser: RefCell<Parser>,
+
+    // tree from last parse
+    tree: RefCell<Option<Tree>>,
+
+    // tree edited since last parse
+    stale: Cell<bool>,
+}
+
+impl TreeSitterHighlight {
+    // make new tree-sitter highlighting
+    pub fn new(
+        language: Language,
+        highlights: &str,
+        fallback: Box<dyn SyntaxHighlight>,
+    ) -> Option<Self> {
+        let mut parser = Parser::new();
+        parser.set_language(language).ok()?;
+
+        let query = Query::new(language, highlights).ok()?;
+
+        Some(Self {
+            fallback,
+            query,
+            parser: RefCell::new(parser),
+            tree:   RefCell::new(None),
+            stale:  Cell::new(false),
+        })
+    }
+
+    // highlighting for vendored grammars
+    pub fn grammars() -> Vec<Box<dyn SyntaxHighlight>> {
+        [
+            Self::new(
+                tree_sitter_rust::language(),
+                tree_sitter_rust::HIGHLIGHT_QUERY,
+                Box::new(RustHighlight::new()),
+            ),
+            Self::new(
+                tree_sitter_javascript::language(),
+                tree_sitter_javascript::HIGHLIGHT_QUERY,
+                Box::new(JavascriptHighlight::new()),
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|it| Box::new(it) as Box<dyn SyntaxHighlight>)
+        .collect()
+    }
+
+    // get highlight for capture name
+    fn capture_highlight(name: &str) -> HighlightType {
+        let kind = name.split('.').next().unwrap_or(name);
+
+        match (kind, name) {
+            (_, "constant.builtin")      => HighlightType::Other(Color::Red),
+            (_, "variable.builtin")      => HighlightType::Other(Color::Magenta),
+            (_, "punctuation.delimiter") => HighlightType::Other(Color::DarkGrey),
+
+            ("comment",     _) => HighlightType::Comment,
+            ("string",      _) => HighlightType::Stringlike,
+            ("number",      _) => HighlightType::Number,
+            ("escape",      _) => HighlightType::Other(Color::Cyan),
+            ("keyword",     _) => HighlightType::Other(Color::Blue),
+            ("type",        _) => HighlightType::Other(Color::Red),
+            ("constructor", _) => HighlightType::Other(Color::Red),
+            ("function",    _) => HighlightType::Other(Color::DarkYellow),
+            ("operator",    _) => HighlightType::Other(Color::Magenta),
+            ("constant",    _) => HighlightType::Other(Color::DarkCyan),
+            ("attribute",   _) => HighlightType::Other(Color::DarkMagenta),
+            ("label",       _) => HighlightType::Other(Color::DarkMagenta),
+
+            _ => HighlightType::Normal,
+        }
+    }
+
+    // get source text for rows
+    fn source(rows: &[Row]) -> String {
+        rows.iter()
+            .map(|row| row.content.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n")
+    }
+
+    // get byte offset for position
+    fn byte_offset(rows: &[Row], (x, y): (usize, usize)) -> usize {
+        rows[..y]
+            .iter()
+            .fold(x, |i, row| i + row.content.len() + 1)
+    }
+
+    // parse rows, returning rows with changed highlighting
+    fn parse(&self, source: &str, rows: &[Row]) -> Option<Vec<usize>> {
+        let mut tree = self.tree.borrow_mut();
+
+        if !self.stale.get() && tree.is_some() {
+            return Some(Vec::new());
+        }
+
+        // reuse edited tree for incremental parse
+        let new_tree = self.parser
+            .borrow_mut()
+            .parse(source, tree.as_ref())?;
+
+        let changed = match tree.as_ref() {
+            Some(old_tree) => {
+                old_tree
+                    .changed_ranges(&new_tree)
+                    .flat_map(|range| {
+                        range.start_point.row..=range.end_point.row
+                    })
+                    .collect()
+            }
+
+            None => (0..rows.len()).collect(),
+        };
+
+        *tree = Some(new_tree);
+        self.stale.set(false);
+
+        Some(changed)
+    }
+
+    // highlight row from tree
+    fn highlight_row(&self, source: &str, at: usize, rows: &mut [Row]) {
+        let tree = self.tree.borrow();
+        let tree = match tree.as_ref() {
+            Some(tree) => tree,
+            None => return,
+        };
+
+        let row = &mut rows[at];
+        let indices = row.render_indices();
+        let row_len = row.content.len();
+
+        row.highlight = vec![HighlightType::Normal; row.render.len()];
+
+        let mut cursor = QueryCursor::new();
+        cursor.set_point_range(Point::new(at, 0)..Point::new(at + 1, 0));
+
+        let captures = cursor.captures(
+            &self.query,
+            tree.root_node(),
+            source.as_bytes(),
+        );
+
+        for (matched, idx) in captures {
+            let capture = matched.captures[idx];
+            let name = &self.query.capture_names()[capture.index as usize];
+
+            let highlight = Self::capture_highlight(name);
+
+            // keep more specific highlights
+            if matches!(highlight, HighlightType::Normal) {
+                continue;
+            }
+
+            let start = capture.node.start_position();
+            let end   = capture.node.end_position();
+
+            if start.row > at || end.row < at {
+                continue;
+            }
+
+            // clamp node to current row
+            let start =
+                if start.row < at { 0 }
+                else { start.column.min(row_len) };
+
+            let end =
+                if end.row > at { row_len }
+                else { end.column.min(row_len) };
+
+            for i in indices[start]..indices[end] {
+                row.highlight[i] = highlight;
+            }
+        }
+    }
+}
+
+impl SyntaxHighlight for TreeSitterHighlight {
+    fn extensions(&self) -> &[&str] {
+        self.fallback.extensions()
+    }
+
+    fn filetype(&self) -> &str {
+        self.fallback.filetype()
+    }
+
+    fn stringlikes(&self) -> &[char] {
+        self.fallback.stringlikes()
+    }
+
+    fn comment_start(&self) -> &str {
+        self.fallback.comment_start()
+    }
+
+    fn multiline_comment(&self) -> Option<(&str, &str)> {
+        self.fallback.multiline_comment()
+    }
+
+    fn indent_triggers(&self) -> &[char] {
+        self.fallback.indent_triggers()
+    }
+
+    fn syntax_color(&self, highlight: &HighlightType) -> Color {
+        self.fallback.syntax_color(highlight)
+    }
+
+    fn update_syntax(&self, at: usize, rows: &mut Vec<Row>) {
+        self.update_rows(at..at + 1, rows);
+    }
+
+    fn update_rows(&self, range: Range<usize>, rows: &mut Vec<Row>) {
+        // parse once for whole range
+        let source = Self::source(rows);
+
+        let changed = match self.parse(&source, rows) {
+            Some(changed) => changed,
+            None => return self.fallback.update_rows(range, rows),
+        };
+
+        // highlight edited rows and rows changed by parse
+        for i in changed.into_iter().chain(range) {
+            if i < rows.len() {
+                self.highlight_row(&source, i, rows);
+            }
+        }
+    }
+
+    fn update_all(&self, rows: &mut Vec<Row>) {
+        // reparse without previous tree
+        self.tree.replace(None);
+
+        let source = Self::source(rows);
+
+        if self.parse(&source, rows).is_none() {
+            return self.fallback.update_all(rows);
+        }
+
+        for i in 0..rows.len() {
+            self.highlight_row(&source, i, rows);
+        }
+    }
+
+    fn edit_rows(&self, rows: &[Row], edit: RowsEdit) {
+        if let Some(tree) = self.tree.borrow_mut().as_mut() {
+            let start_byte = Self::byte_offset(rows, edit.start);
+
+            tree.edit(&InputEdit {
+                start_byte,
+                old_end_byte: start_byte + edit.old_len,
+                new_end_byte: start_byte + edit.new_len,
+
+                start_position:   Point::new(edit.start.1,   edit.start.0),
+                old_end_position: Point::new(edit.old_end.1, edit.old_end.0),
+                new_end_position: Point::new(edit.new_end.1, edit.new_end.0),
+            });
+
+            self.stale.set(true);
+        }
+    }
+}
+
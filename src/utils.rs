@@ -1,14 +1,25 @@
 // create prompt using message
 macro_rules! prompt {
     ($output:expr, $args:tt) => {
-        prompt!($output, $args, true, |&_, _, _| {})
+        prompt!($output, $args, true, |&_, _, _| {}, None, None)
+    };
+
+    // prompt with tab completion
+    ($output:expr, $args:tt, $complete:expr) => {
+        prompt!($output, $args, true, |&_, _, _| {}, Some($complete), None)
     };
 
     // arguments with optional trailing comma
-    ($buffer:expr, $prompt:expr, $move_cursor:expr, $callback:expr $(,)?) => {{
+    ($buffer:expr, $prompt:expr, $move_cursor:expr, $callback:expr $(,)?) => {
+        prompt!($buffer, $prompt, $move_cursor, $callback, None, None)
+    };
+
+    ($buffer:expr, $prompt:expr, $move_cursor:expr, $callback:expr, $complete:expr, $history:expr $(,)?) => {{
         use crate::buffer::Buffer;
+        use crate::complete::{Completer, Completion};
         use crate::config::{Config, CursorShape};
-        use crate::reader::Reader;
+        use crate::prompts::{PromptHistory, PromptInput, PromptKind};
+        use crate::reader::{Input, Reader};
 
         use crossterm::event::{KeyEvent, KeyCode, KeyModifiers};
         use crossterm::{execute, cursor};
@@ -19,7 +30,22 @@ macro_rules! prompt {
         let prompt: &str        = $prompt;
         let move_cursor: bool   = $move_cursor;
 
-        let mut input = String::new();
+        let complete: Option<fn(&str) -> Completion> = $complete;
+        let mut completer = complete.map(Completer::new);
+
+        // previous inputs, most recent first
+        let history_kind: Option<PromptKind> = $history;
+        let history = history_kind
+            .map(|kind| PromptHistory::load().entries(kind).clone())
+            .unwrap_or_default();
+
+        // recalled history entry
+        let mut history_idx: Option<usize> = None;
+
+        // whether arrows stop recalling history, after typing or recalling search
+        let mut edited = false;
+
+        let mut input = PromptInput::default();
 
         // convert cursor into character
         let cursor_shape = if !move_cursor {
@@ -31,15 +57,30 @@ macro_rules! prompt {
         } else { "" };
 
         loop {
-            let input_prompt = format!(
-                "[prompt] {}: {}{}",
+            let before_cursor = format!(
+                "[prompt] {}: {}",
                 prompt,
-                input,
+                &input.text[..input.pos],
+            );
+
+            let input_prompt = format!(
+                "{}{}{}",
+                before_cursor,
                 cursor_shape,
+                &input.text[input.pos..],
             );
 
-            // show currently typed text
-            buffer.message.set_message(input_prompt.clone());
+            // show currently typed text and candidates
+            match completer.as_ref().and_then(Completer::candidates) {
+                Some(candidates) => buffer.message.set_message(format!(
+                    "{}  {}",
+                    input_prompt,
+                    candidates,
+                )),
+
+                None => buffer.message.set_message(input_prompt),
+            }
+
             buffer.refresh_screen()?;
 
             // move cursor to prompt
@@ -47,13 +88,40 @@ macro_rules! prompt {
                 execute!(
                     stdout(),
                     cursor::MoveTo(
-                        input_prompt.len()       as u16,
-                        (buffer.term_size.1 + 1) as u16,
+                        before_cursor.chars().count() as u16,
+                        (buffer.term_size.1 + 1)      as u16,
                     ),
                 )?;
             }
 
-            let key = Reader::read_key()?;
+            let key = match Reader::read_input()? {
+                Input::Key(key) => key,
+
+                // insert pasted text on one line within term width
+                Input::Paste(text) => {
+                    if let Some(completer) = &mut completer {
+                        completer.reset();
+                    }
+
+                    input.paste(&text, buffer.term_size.0.saturating_sub(prompt.len()));
+                    edited = true;
+
+                    $callback(buffer, &input.text, KeyCode::Null);
+                    continue;
+                }
+
+                _ => continue,
+            };
+
+            // key passed to callback
+            let mut code = key.code;
+
+            // stop cycling candidates on other keys
+            if key.code != KeyCode::Tab {
+                if let Some(completer) = &mut completer {
+                    completer.reset();
+                }
+            }
 
             match key {
                 // cancel prompt
@@ -61,55 +129,126 @@ macro_rules! prompt {
                     code: KeyCode::Esc, ..
                 } => {
                     buffer.message.set_message(String::new());
-                    input.clear();
-                    $callback(buffer, &input, key.code);
+                    input.set("");
+                    $callback(buffer, &input.text, key.code);
                     break;
                 }
 
                 // submit input
                 KeyEvent {
                     code:      KeyCode::Enter,
-                    modifiers: KeyModifiers::NONE,
-                } => {
-                    if !input.is_empty() {
-                        buffer.message.set_message(String::new());
-                        $callback(buffer, &input, key.code);
-                        break;
+                    modifiers: KeyModifiers::NONE, ..
+                } if !input.text.is_empty() => {
+                    buffer.message.set_message(String::new());
+
+                    if let Some(kind) = history_kind {
+                        PromptHistory::add(kind, &input.text);
                     }
+
+                    $callback(buffer, &input.text, key.code);
+                    break;
+                }
+
+                // recall older input
+                KeyEvent {
+                    code:      KeyCode::Up,
+                    modifiers: KeyModifiers::NONE, ..
+                } if !edited && !history.is_empty() => {
+                    let idx = history_idx.map_or(0, |i| (i + 1).min(history.len() - 1));
+
+                    input.set(&history[idx]);
+                    history_idx = Some(idx);
+                    code = KeyCode::Null;
+
+                    edited = !history_kind.is_some_and(PromptKind::cycles_history);
                 }
 
+                // recall newer input
+                KeyEvent {
+                    code:      KeyCode::Down,
+                    modifiers: KeyModifiers::NONE, ..
+                } if !edited && history_idx.is_some() => {
+                    history_idx = history_idx.unwrap().checked_sub(1);
+                    input.set(history_idx.map_or("", |i| &history[i]));
+                    code = KeyCode::Null;
+                }
+
+                // move cursor in input
+                KeyEvent {
+                    code:      KeyCode::Left,
+                    modifiers: KeyModifiers::NONE, ..
+                } => input.move_left(),
+
+                KeyEvent {
+                    code:      KeyCode::Right,
+                    modifiers: KeyModifiers::NONE, ..
+                } => input.move_right(),
+
+                KeyEvent {
+                    code:      KeyCode::Home,
+                    modifiers: KeyModifiers::NONE, ..
+                } => input.pos = 0,
+
+                KeyEvent {
+                    code:      KeyCode::End,
+                    modifiers: KeyModifiers::NONE, ..
+                } => input.pos = input.text.len(),
+
                 // delete char
                 KeyEvent {
                     code:      KeyCode::Backspace,
-                    modifiers: KeyModifiers::NONE,
+                    modifiers: KeyModifiers::NONE, ..
                 } => {
-                    input.pop();
+                    input.delete_char();
+                    edited = true;
+                }
+
+                // delete word
+                KeyEvent {
+                    code:      KeyCode::Char('w'),
+                    modifiers: KeyModifiers::CONTROL, ..
+                } => {
+                    input.delete_word();
+                    edited = true;
+                }
+
+                // complete input
+                KeyEvent {
+                    code:      KeyCode::Tab,
+                    modifiers: KeyModifiers::NONE, ..
+                } if completer.is_some() => {
+                    completer.as_mut().unwrap().complete(&mut input.text);
+
+                    input.pos = input.text.len();
+                    edited    = true;
                 }
 
                 // add character to input
                 KeyEvent {
                     code:      code @ (KeyCode::Char(..) | KeyCode::Tab),
-                    modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+                    modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT, ..
                 } => {
                     let max_len = buffer.term_size.0 - prompt.len();
 
                     // confirm input does not exceed term width
-                    if input.len() < max_len {
-                        input.push(match code {
+                    if input.text.len() < max_len {
+                        input.insert_str(match code {
                             KeyCode::Tab       => '\t',
                             KeyCode::Char(chr) => chr,
                             _ => unreachable!(),
-                        });
+                        }.encode_utf8(&mut [0; 4]));
                     }
+
+                    edited = true;
                 }
 
                 _ => {}
             }
 
-            $callback(buffer, &input, key.code);
+            $callback(buffer, &input.text, code);
         }
 
-        if input.is_empty() { None } else { Some(input) }
+        if input.text.is_empty() { None } else { Some(input.text) }
     }};
 }
 